@@ -0,0 +1,89 @@
+# German. Umlauts are not in flappy-font.ttf, so it is drawn with DejaVu.
+
+-language = DEUTSCH
+-font = fonts/DejaVuSans-Bold.ttf
+
+title = FLAPPY BIRD
+menu-editor = EDITOR
+menu-stats = STATISTIK
+menu-achievements = ERFOLGE
+menu-settings = EINSTELLUNGEN
+menu-back = ZURÜCK
+
+mode-classic = KLASSISCH
+mode-time-attack = ZEITRENNEN
+mode-practice = TRAINING
+mode-daily-challenge = TAGESAUFGABE
+mode-level = LEVEL
+mode-playtest = TESTSPIEL
+
+stats-title = STATISTIK
+stats-page-bests = < BESTWERTE >
+stats-page-lifetime = < INSGESAMT >
+stats-best = { $mode } BESTWERT { $score }
+stats-time-attack = ZEITRENNEN #{ $rank } { $score }
+stats-games = SPIELE { $count }
+stats-pipes = RÖHREN { $count }
+stats-flaps = FLÜGELSCHLÄGE { $count }
+stats-best-average = BESTWERT { $best } SCHNITT { $average }
+stats-longest-session = LÄNGSTE SITZUNG { $time }
+
+achievements-title = ERFOLGE
+achievement-unlocked = FREIGESCHALTET: { $name }
+achievement-first_point = ERSTER FLUG
+achievement-first_point-description = ERZIELE EINEN PUNKT
+achievement-score_10 = ZWEISTELLIG
+achievement-score_10-description = ERZIELE 10 PUNKTE IN EINEM LAUF
+achievement-score_50 = HALBES HUNDERT
+achievement-score_50-description = ERZIELE 50 PUNKTE IN EINEM LAUF
+achievement-low_flyer = TIEFFLIEGER
+achievement-low_flyer-description = 5 RÖHREN OHNE FLÜGELSCHLAG ÜBER DER MITTE
+achievement-games_10 = STAMMGAST
+achievement-games_10-description = SPIELE 10 RUNDEN
+achievement-games_100 = TREUE SEELE
+achievement-games_100-description = SPIELE 100 RUNDEN
+achievement-flaps_1000 = MÜDE FLÜGEL
+achievement-flaps_1000-description = SCHLAGE 1000 MAL MIT DEN FLÜGELN
+achievement-first_pipe_deaths_10 = NICHT SCHON WIEDER
+achievement-first_pipe_deaths_10-description = STIRB 10 MAL AN DER ERSTEN RÖHRE
+
+settings-title = EINSTELLUNGEN
+settings-group-general = < ALLGEMEIN >
+settings-group-display = < ANZEIGE >
+settings-group-accessibility = < BARRIEREFREIHEIT >
+settings-coins = MÜNZEN { $coins }
+settings-price = { $price } MÜNZEN
+settings-on = AN
+settings-off = AUS
+settings-language = SPRACHE { $language }
+settings-volume = LAUTSTÄRKE { $percent }%
+settings-skin = VOGEL { $skin }{ $price }
+settings-theme = DESIGN { $theme }{ $price }
+settings-flap-key = FLATTERTASTE { $key }
+settings-reduced-motion = WENIGER BEWEGUNG { $value }
+settings-high-contrast = HOHER KONTRAST { $value }
+settings-ui-scale = TEXTGRÖSSE { $percent }%
+settings-practice-no-crash = TRAINING OHNE ABSTURZ { $value }
+settings-practice-speed = TRAININGSTEMPO { $percent }%
+settings-display = ANZEIGE { $mode }
+settings-vsync = VSYNC { $value }
+settings-fps-cap = FPS-LIMIT { $cap }
+settings-fps-cap-off = FPS-LIMIT AUS
+skin-red = ROT
+skin-blue = BLAU
+skin-yellow = GELB
+theme-day = TAG
+theme-night = NACHT
+key-space = LEERTASTE
+key-up = HOCH
+display-windowed = FENSTER
+display-borderless = RAHMENLOS
+display-fullscreen = VOLLBILD
+
+ready-title = MACH DICH BEREIT
+ready-hint = FLATTERN ZUM STARTEN
+game-over = SPIEL VORBEI
+level-complete = LEVEL GESCHAFFT
+
+practice-no-crash = OHNE ABSTURZ
+practice-checkpoints = KONTROLLPUNKTE
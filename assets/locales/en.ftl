@@ -0,0 +1,111 @@
+# English, the fallback for anything another locale leaves out.
+#
+# Only a subset of Fluent is understood: `key = value` messages, indented
+# lines continuing a message on a new line, and `{ $name }` placeholders.
+# `-language` is the name shown in the settings and `-font` the font the
+# locale is drawn with.
+
+-language = ENGLISH
+-font = flappy-font.ttf
+
+title = FLAPPY BIRD
+menu-editor = EDITOR
+menu-stats = STATS
+menu-achievements = ACHIEVEMENTS
+menu-settings = SETTINGS
+menu-back = BACK
+
+mode-classic = CLASSIC
+mode-time-attack = TIME ATTACK
+mode-practice = PRACTICE
+mode-daily-challenge = DAILY CHALLENGE
+mode-level = LEVEL
+mode-playtest = PLAYTEST
+
+stats-title = STATS
+stats-page-bests = < BESTS >
+stats-page-lifetime = < LIFETIME >
+stats-best = { $mode } BEST { $score }
+stats-time-attack = TIME ATTACK #{ $rank } { $score }
+stats-games = GAMES { $count }
+stats-pipes = PIPES { $count }
+stats-flaps = FLAPS { $count }
+stats-best-average = BEST { $best } AVERAGE { $average }
+stats-longest-session = LONGEST SESSION { $time }
+
+achievements-title = ACHIEVEMENTS
+achievement-unlocked = UNLOCKED { $name }
+achievement-first_point = FIRST FLIGHT
+achievement-first_point-description = SCORE A POINT
+achievement-score_10 = DOUBLE DIGITS
+achievement-score_10-description = SCORE 10 IN ONE RUN
+achievement-score_50 = HALF CENTURY
+achievement-score_50-description = SCORE 50 IN ONE RUN
+achievement-low_flyer = LOW FLYER
+achievement-low_flyer-description = PASS 5 PIPES WITHOUT FLAPPING ABOVE CENTER
+achievement-games_10 = REGULAR
+achievement-games_10-description = PLAY 10 GAMES
+achievement-games_100 = DEVOTED
+achievement-games_100-description = PLAY 100 GAMES
+achievement-flaps_1000 = SORE WINGS
+achievement-flaps_1000-description = FLAP 1000 TIMES
+achievement-first_pipe_deaths_10 = NOT AGAIN
+achievement-first_pipe_deaths_10-description = DIE ON THE FIRST PIPE 10 TIMES
+
+settings-title = SETTINGS
+settings-group-general = < GENERAL >
+settings-group-display = < DISPLAY >
+settings-group-accessibility = < ACCESSIBILITY >
+settings-coins = COINS { $coins }
+settings-price = { $price } COINS
+settings-on = ON
+settings-off = OFF
+settings-language = LANGUAGE { $language }
+settings-volume = VOLUME { $percent }%
+settings-skin = BIRD { $skin }{ $price }
+settings-theme = THEME { $theme }{ $price }
+settings-flap-key = FLAP KEY { $key }
+settings-reduced-motion = REDUCED MOTION { $value }
+settings-high-contrast = HIGH CONTRAST { $value }
+settings-ui-scale = UI SCALE { $percent }%
+settings-practice-no-crash = PRACTICE NO CRASH { $value }
+settings-practice-speed = PRACTICE SPEED { $percent }%
+settings-display = DISPLAY { $mode }
+settings-vsync = VSYNC { $value }
+settings-fps-cap = FPS CAP { $cap }
+settings-fps-cap-off = FPS CAP OFF
+skin-red = RED
+skin-blue = BLUE
+skin-yellow = YELLOW
+theme-day = DAY
+theme-night = NIGHT
+key-space = SPACE
+key-up = UP
+key-w = W
+display-windowed = WINDOWED
+display-borderless = BORDERLESS
+display-fullscreen = FULLSCREEN
+
+ready-title = GET READY
+ready-hint = FLAP TO START
+game-over = GAME OVER
+level-complete = LEVEL COMPLETE
+
+practice-no-crash = NO CRASH
+practice-checkpoints = CHECKPOINTS
+practice-label = { $mode } { $percent }%
+
+editor-help-scroll = WHEEL OR ARROWS SCROLL  CLICK ADDS OR DRAGS A COLUMN
+editor-help-edit = DRAG A GAP EDGE TO RESIZE  RIGHT CLICK DELETES
+editor-help-keys = B BEHAVIOR  C COIN  P POWER UP  T TEST  S SAVE  ESC BACK
+editor-too-close = TOO CLOSE TO ANOTHER COLUMN
+editor-nothing-to-test = NO COLUMNS AHEAD TO TEST
+editor-saved = SAVED { $path }
+editor-save-failed = COULD NOT SAVE
+editor-status = COLUMN { $column } OF { $count }  { $behavior }  COIN { $coin }  POWER UP { $power-up }
+editor-level = { $name }  { $count } COLUMNS
+editor-none = NONE
+behavior-static = STATIC
+behavior-oscillating = OSCILLATING
+behavior-breathing = BREATHING
+behavior-drop-in = DROP IN
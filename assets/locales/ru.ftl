@@ -0,0 +1,89 @@
+# Russian. Cyrillic is not in flappy-font.ttf, so it is drawn with DejaVu.
+
+-language = РУССКИЙ
+-font = fonts/DejaVuSans-Bold.ttf
+
+title = FLAPPY BIRD
+menu-editor = РЕДАКТОР
+menu-stats = СТАТИСТИКА
+menu-achievements = ДОСТИЖЕНИЯ
+menu-settings = НАСТРОЙКИ
+menu-back = НАЗАД
+
+mode-classic = КЛАССИКА
+mode-time-attack = НА ВРЕМЯ
+mode-practice = ТРЕНИРОВКА
+mode-daily-challenge = ЗАДАНИЕ ДНЯ
+mode-level = УРОВЕНЬ
+mode-playtest = ПРОВЕРКА
+
+stats-title = СТАТИСТИКА
+stats-page-bests = < РЕКОРДЫ >
+stats-page-lifetime = < ВСЕГО >
+stats-best = { $mode } РЕКОРД { $score }
+stats-time-attack = НА ВРЕМЯ #{ $rank } { $score }
+stats-games = ИГРЫ { $count }
+stats-pipes = ТРУБЫ { $count }
+stats-flaps = ВЗМАХИ { $count }
+stats-best-average = РЕКОРД { $best } В СРЕДНЕМ { $average }
+stats-longest-session = ДОЛЬШЕ ВСЕГО { $time }
+
+achievements-title = ДОСТИЖЕНИЯ
+achievement-unlocked = ОТКРЫТО: { $name }
+achievement-first_point = ПЕРВЫЙ ПОЛЁТ
+achievement-first_point-description = НАБЕРИ ОДНО ОЧКО
+achievement-score_10 = ДВУЗНАЧНОЕ
+achievement-score_10-description = НАБЕРИ 10 ОЧКОВ ЗА ЗАБЕГ
+achievement-score_50 = ПОЛСОТНИ
+achievement-score_50-description = НАБЕРИ 50 ОЧКОВ ЗА ЗАБЕГ
+achievement-low_flyer = НА БРЕЮЩЕМ
+achievement-low_flyer-description = 5 ТРУБ БЕЗ ВЗМАХОВ ВЫШЕ СЕРЕДИНЫ
+achievement-games_10 = ЗАВСЕГДАТАЙ
+achievement-games_10-description = СЫГРАЙ 10 ИГР
+achievement-games_100 = ПРЕДАННЫЙ
+achievement-games_100-description = СЫГРАЙ 100 ИГР
+achievement-flaps_1000 = УСТАЛЫЕ КРЫЛЬЯ
+achievement-flaps_1000-description = СДЕЛАЙ 1000 ВЗМАХОВ
+achievement-first_pipe_deaths_10 = ОПЯТЬ?
+achievement-first_pipe_deaths_10-description = РАЗБЕЙСЯ О ПЕРВУЮ ТРУБУ 10 РАЗ
+
+settings-title = НАСТРОЙКИ
+settings-group-general = < ОБЩИЕ >
+settings-group-display = < ЭКРАН >
+settings-group-accessibility = < ДОСТУПНОСТЬ >
+settings-coins = МОНЕТЫ { $coins }
+settings-price = { $price } МОНЕТ
+settings-on = ВКЛ
+settings-off = ВЫКЛ
+settings-language = ЯЗЫК { $language }
+settings-volume = ГРОМКОСТЬ { $percent }%
+settings-skin = ПТИЦА { $skin }{ $price }
+settings-theme = ТЕМА { $theme }{ $price }
+settings-flap-key = КЛАВИША { $key }
+settings-reduced-motion = МЕНЬШЕ ДВИЖЕНИЯ { $value }
+settings-high-contrast = ВЫСОКИЙ КОНТРАСТ { $value }
+settings-ui-scale = МАСШТАБ { $percent }%
+settings-practice-no-crash = ТРЕНИРОВКА БЕЗ АВАРИЙ { $value }
+settings-practice-speed = СКОРОСТЬ ТРЕНИРОВКИ { $percent }%
+settings-display = ЭКРАН { $mode }
+settings-vsync = VSYNC { $value }
+settings-fps-cap = ПРЕДЕЛ FPS { $cap }
+settings-fps-cap-off = ПРЕДЕЛ FPS ВЫКЛ
+skin-red = КРАСНАЯ
+skin-blue = СИНЯЯ
+skin-yellow = ЖЁЛТАЯ
+theme-day = ДЕНЬ
+theme-night = НОЧЬ
+key-space = ПРОБЕЛ
+key-up = ВВЕРХ
+display-windowed = ОКНО
+display-borderless = БЕЗ РАМКИ
+display-fullscreen = ВО ВЕСЬ ЭКРАН
+
+ready-title = ПРИГОТОВЬСЯ
+ready-hint = ВЗМАХНИ, ЧТОБЫ НАЧАТЬ
+game-over = ИГРА ОКОНЧЕНА
+level-complete = УРОВЕНЬ ПРОЙДЕН
+
+practice-no-crash = БЕЗ АВАРИЙ
+practice-checkpoints = КОНТРОЛЬНЫЕ ТОЧКИ
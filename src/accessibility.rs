@@ -0,0 +1,131 @@
+use crate::{components::*, settings::Settings};
+use bevy::{
+    math::{const_vec2, vec3},
+    prelude::*,
+    ui::UiSystem,
+};
+
+// thickness of the high contrast outlines in pixels
+pub const OUTLINE_WIDTH: f32 = 3.0;
+const OUTLINE_COLOR: Color = Color::BLACK;
+// in front of the sprite being outlined
+const OUTLINE_Z: f32 = 0.1;
+// sizes of the unscaled pipe and bird sprites
+const PIPE_SPRITE: Vec2 = const_vec2!([PIPE_WIDTH, PIPE_HEIGHT]);
+const BIRD_SPRITE: Vec2 = const_vec2!([34.0, 24.0]);
+
+// One edge of a high contrast outline, a child of the sprite it outlines.
+#[derive(Component)]
+pub struct Outline;
+
+// Text whose font size follows the UI scale, from its size at 100%.
+#[derive(Component)]
+pub struct ScaledText(pub f32);
+
+// UI node whose size follows the UI scale, from its size at 100%.
+#[derive(Component)]
+pub struct ScaledNode(pub Vec2);
+
+// Frames a sprite of `size` with four edges. The edges are children, so
+// they are drawn in the sprite's own scale: `scale` keeps them
+// `OUTLINE_WIDTH` pixels wide on screen.
+fn spawn_outline(commands: &mut Commands, sprite: Entity, size: Vec2, scale: f32) {
+    let line = OUTLINE_WIDTH / scale;
+    let edges = [
+        (
+            vec3(0.0, size.y / 2.0, OUTLINE_Z),
+            Vec2::new(size.x + line, line),
+        ),
+        (
+            vec3(0.0, -size.y / 2.0, OUTLINE_Z),
+            Vec2::new(size.x + line, line),
+        ),
+        (
+            vec3(size.x / 2.0, 0.0, OUTLINE_Z),
+            Vec2::new(line, size.y + line),
+        ),
+        (
+            vec3(-size.x / 2.0, 0.0, OUTLINE_Z),
+            Vec2::new(line, size.y + line),
+        ),
+    ];
+    commands.entity(sprite).with_children(|parent| {
+        for (offset, edge) in edges {
+            parent
+                .spawn_bundle(SpriteBundle {
+                    sprite: Sprite {
+                        color: OUTLINE_COLOR,
+                        custom_size: Some(edge),
+                        ..default()
+                    },
+                    transform: Transform::from_translation(offset),
+                    visibility: Visibility { is_visible: false },
+                    ..default()
+                })
+                .insert(Outline);
+        }
+    });
+}
+
+pub fn add_outlines(
+    mut commands: Commands,
+    pipe_query: Query<(Entity, &Transform), Added<Pipe>>,
+    player_query: Query<(Entity, &Transform), Added<Player>>,
+) {
+    for (entity, transform) in pipe_query.iter() {
+        spawn_outline(&mut commands, entity, PIPE_SPRITE, transform.scale.x);
+    }
+    for (entity, transform) in player_query.iter() {
+        spawn_outline(&mut commands, entity, BIRD_SPRITE, transform.scale.x);
+    }
+}
+
+// Outlines show with high contrast, and only while what they outline does.
+pub fn update_outlines(
+    settings: Res<Settings>,
+    sprite_query: Query<&Visibility, Without<Outline>>,
+    mut outline_query: Query<(&Parent, &mut Visibility), With<Outline>>,
+) {
+    let high_contrast = settings.accessibility.high_contrast;
+    for (parent, mut visibility) in outline_query.iter_mut() {
+        let shown = sprite_query
+            .get(parent.0)
+            .map_or(false, |sprite| sprite.is_visible);
+        let visible = high_contrast && shown;
+        if visibility.is_visible != visible {
+            visibility.is_visible = visible;
+        }
+    }
+}
+
+// Scales new texts and nodes, and all of them when the setting changes.
+pub fn apply_ui_scale(
+    settings: Res<Settings>,
+    mut text_query: Query<(&ScaledText, &mut Text, ChangeTrackers<ScaledText>)>,
+    mut node_query: Query<(&ScaledNode, &mut Style, ChangeTrackers<ScaledNode>)>,
+) {
+    let scale = settings.accessibility.ui_scale;
+    for (ScaledText(size), mut text, tracker) in text_query.iter_mut() {
+        if settings.is_changed() || tracker.is_added() {
+            for section in text.sections.iter_mut() {
+                section.style.font_size = size * scale;
+            }
+        }
+    }
+    for (ScaledNode(size), mut style, tracker) in node_query.iter_mut() {
+        if settings.is_changed() || tracker.is_added() {
+            let size = (*size * scale).min(SCREEN);
+            style.size = Size::new(Val::Px(size.x), Val::Px(size.y));
+        }
+    }
+}
+
+pub struct AccessibilityPlugin;
+
+impl Plugin for AccessibilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_to_stage(CoreStage::PostUpdate, add_outlines)
+            .add_system(update_outlines)
+            .add_system_to_stage(CoreStage::PostUpdate, apply_ui_scale.before(UiSystem::Flex));
+    }
+}
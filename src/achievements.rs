@@ -0,0 +1,320 @@
+use crate::{
+    accessibility::ScaledText,
+    components::*,
+    locale::{Localization, LocalizedFont},
+    menu::*,
+    modes::GameMode,
+    save::SaveData,
+    stats::record_lifetime_stats,
+    telemetry::{track_run, RunTelemetry},
+};
+use bevy::prelude::*;
+use serde::Deserialize;
+
+pub const TOAST_SECS: f32 = 3.0;
+// toasts fade out over the end of their time
+const TOAST_FADE_SECS: f32 = 0.5;
+const TOAST_HEIGHT: f32 = 64.0;
+const TOAST_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.7);
+const DESCRIPTION_FONT_SIZE: f32 = 22.0;
+const LOCKED_TEXT_COLOR: Color = Color::rgb(0.55, 0.55, 0.55);
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub enum Goal {
+    Score(usize),
+    LowPipes(usize),
+    Games(usize),
+    Flaps(usize),
+    FirstPipeDeaths(usize),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Achievement {
+    pub id: String,
+    pub goal: Goal,
+}
+
+impl Achievement {
+    // name and description come from the locale files, keyed by id
+    pub fn name_key(&self) -> String {
+        format!("achievement-{}", self.id)
+    }
+
+    pub fn description_key(&self) -> String {
+        format!("achievement-{}-description", self.id)
+    }
+}
+
+// Loaded from `assets/achievements.ron`.
+pub struct Achievements(pub Vec<Achievement>);
+
+impl Achievements {
+    pub fn load() -> Self {
+        Achievements(
+            ron::from_str(include_str!("../assets/achievements.ron"))
+                .expect("assets/achievements.ron is not valid"),
+        )
+    }
+}
+
+// index into `Achievements`
+pub struct AchievementUnlockedEvent(pub usize);
+
+// Pipes passed in a row this run without flapping above the middle of the
+// screen.
+#[derive(Default)]
+pub struct LowPipeStreak(pub usize);
+
+#[derive(Component)]
+pub struct AchievementToast(pub Timer);
+
+#[derive(Component)]
+pub struct AchievementsScreen;
+
+pub fn reset_low_pipe_streak(mut streak: ResMut<LowPipeStreak>) {
+    streak.0 = 0;
+}
+
+// Runs after the lifetime stats took in a finished run and before telemetry
+// lets go of it.
+#[allow(clippy::too_many_arguments)]
+pub fn track_achievements(
+    mode: Res<GameMode>,
+    game_state: Res<State<GameState>>,
+    scoreboard: Res<Scoreboard>,
+    telemetry: Res<RunTelemetry>,
+    achievements: Res<Achievements>,
+    mut streak: ResMut<LowPipeStreak>,
+    mut save_data: ResMut<SaveData>,
+    mut flap_events: EventReader<FlapEvent>,
+    mut scored_events: EventReader<PointScoredEvent>,
+    mut unlocked_events: EventWriter<AchievementUnlockedEvent>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    let record = match &telemetry.0 {
+        Some(record) => record,
+        None => return,
+    };
+    let bird_y = player_query
+        .get_single()
+        .map_or(0.0, |transform| transform.translation.y);
+
+    if flap_events.iter().count() > 0 && bird_y > 0.0 {
+        streak.0 = 0;
+    }
+    streak.0 += scored_events.iter().count();
+
+    let ended = matches!(
+        game_state.current(),
+        GameState::GameOver | GameState::LevelComplete
+    );
+    let counted = *mode != GameMode::Playtest;
+    let mut changed = false;
+    if ended && counted && record.death.is_some() && scoreboard.score == 0 {
+        save_data.achievements.first_pipe_deaths += 1;
+        changed = true;
+    }
+
+    let scored = mode.records_high_score();
+    for (index, achievement) in achievements.0.iter().enumerate() {
+        if save_data.achievements.unlocked.contains(&achievement.id) {
+            continue;
+        }
+        let reached = match achievement.goal {
+            Goal::Score(score) => scored && scoreboard.score >= score,
+            Goal::LowPipes(pipes) => scored && streak.0 >= pipes,
+            Goal::Games(games) => save_data.lifetime.games >= games,
+            Goal::Flaps(flaps) => save_data.lifetime.flaps >= flaps,
+            Goal::FirstPipeDeaths(deaths) => save_data.achievements.first_pipe_deaths >= deaths,
+        };
+        if reached {
+            save_data.achievements.unlocked.push(achievement.id.clone());
+            unlocked_events.send(AchievementUnlockedEvent(index));
+            changed = true;
+        }
+    }
+    if changed {
+        save_data.save();
+    }
+}
+
+pub fn show_toasts(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    localization: Res<Localization>,
+    achievements: Res<Achievements>,
+    mut unlocked_events: EventReader<AchievementUnlockedEvent>,
+    toast_query: Query<&AchievementToast>,
+) {
+    // stacked under the ones still showing
+    let below = toast_query.iter().count();
+    for (shown, AchievementUnlockedEvent(index)) in (below..).zip(unlocked_events.iter()) {
+        let achievement = &achievements.0[*index];
+        commands
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position: Rect {
+                        left: Val::Px(0.0),
+                        right: Val::Px(0.0),
+                        top: Val::Px(shown as f32 * TOAST_HEIGHT),
+                        ..default()
+                    },
+                    size: Size::new(Val::Auto, Val::Px(TOAST_HEIGHT)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                color: UiColor(TOAST_COLOR),
+                ..default()
+            })
+            .insert(AchievementToast(Timer::from_seconds(TOAST_SECS, false)))
+            .with_children(|parent| {
+                parent
+                    .spawn_bundle(TextBundle {
+                        text: Text::with_section(
+                            localization.format(
+                                "achievement-unlocked",
+                                &[("name", localization.get(&achievement.name_key()))],
+                            ),
+                            TextStyle {
+                                font: localization.font(&asset_server),
+                                font_size: MENU_FONT_SIZE,
+                                color: Color::WHITE,
+                            },
+                            default(),
+                        ),
+                        ..default()
+                    })
+                    .insert(LocalizedFont);
+            });
+    }
+}
+
+pub fn update_toasts(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut toast_query: Query<(Entity, &mut AchievementToast, &mut UiColor, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (entity, mut toast, mut color, children) in toast_query.iter_mut() {
+        toast.0.tick(time.delta());
+        if toast.0.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        let left = toast.0.duration().as_secs_f32() - toast.0.elapsed_secs();
+        let alpha = (left / TOAST_FADE_SECS).min(1.0);
+        color.0.set_a(TOAST_COLOR.a() * alpha);
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].style.color.set_a(alpha);
+            }
+        }
+    }
+}
+
+fn spawn_achievement_text(
+    parent: &mut ChildBuilder,
+    font: Handle<Font>,
+    value: &str,
+    font_size: f32,
+    color: Color,
+) {
+    parent
+        .spawn_bundle(TextBundle {
+            text: Text::with_section(
+                value,
+                TextStyle {
+                    font,
+                    font_size,
+                    color,
+                },
+                default(),
+            ),
+            ..default()
+        })
+        .insert(ScaledText(font_size));
+}
+
+pub fn setup_achievements_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    localization: Res<Localization>,
+    achievements: Res<Achievements>,
+    save_data: Res<SaveData>,
+) {
+    let font = localization.font(&asset_server);
+    commands
+        .spawn_bundle(menu_root())
+        .insert(AchievementsScreen)
+        .with_children(|parent| {
+            spawn_menu_title(
+                parent,
+                font.clone(),
+                &localization.get("achievements-title"),
+            );
+            for achievement in achievements.0.iter() {
+                let unlocked = save_data.achievements.unlocked.contains(&achievement.id);
+                let (mark, color) = if unlocked {
+                    ("[X]", Color::WHITE)
+                } else {
+                    ("[ ]", LOCKED_TEXT_COLOR)
+                };
+                let name = format!("{} {}", mark, localization.get(&achievement.name_key()));
+                spawn_achievement_text(parent, font.clone(), &name, MENU_FONT_SIZE, color);
+                spawn_achievement_text(
+                    parent,
+                    font.clone(),
+                    &localization.get(&achievement.description_key()),
+                    DESCRIPTION_FONT_SIZE,
+                    color,
+                );
+            }
+            spawn_menu_item(parent, font.clone(), 0, &localization.get("menu-back"));
+        });
+}
+
+pub fn handle_achievements_menu(
+    mut game_state: ResMut<State<GameState>>,
+    mut menu_events: EventReader<MenuEvent>,
+) {
+    if menu_events
+        .iter()
+        .any(|event| matches!(event.action, MenuAction::Activate | MenuAction::Back))
+    {
+        game_state.set(GameState::MainMenu).unwrap();
+    }
+}
+
+pub struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Achievements::load())
+            .init_resource::<LowPipeStreak>()
+            .add_event::<AchievementUnlockedEvent>()
+            .add_system_set(
+                SystemSet::on_enter(GameState::Running).with_system(reset_low_pipe_streak),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                track_achievements
+                    .after(record_lifetime_stats)
+                    .before(track_run),
+            )
+            .add_system(show_toasts)
+            .add_system(update_toasts)
+            .add_system_set(
+                SystemSet::on_enter(GameState::Achievements).with_system(setup_achievements_screen),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::Achievements).with_system(handle_achievements_menu),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::Achievements)
+                    .with_system(despawn_screen::<AchievementsScreen>),
+            );
+    }
+}
@@ -0,0 +1,167 @@
+use crate::{components::*, settings::Settings};
+use bevy::prelude::*;
+use rand::prelude::*;
+
+pub const COLLISION_TRAUMA: f32 = 0.6;
+pub const COLLISION_FLASH_SECS: f32 = 0.25;
+// how opaque a flash starts out
+pub const FLASH_ALPHA: f32 = 0.9;
+
+// The camera the world is drawn with, the one that shakes.
+#[derive(Component)]
+pub struct GameCamera;
+
+#[derive(Component)]
+pub struct FlashOverlay;
+
+// Adds trauma, 1 being the strongest shake. Trauma adds up and is capped.
+pub struct CameraShakeEvent(pub f32);
+
+pub struct ScreenFlashEvent {
+    pub color: Color,
+    pub secs: f32,
+}
+
+// Trauma based shake: the offset grows with the square of the trauma, which
+// wears off at `decay` per second.
+pub struct CameraShake {
+    pub trauma: f32,
+    pub decay: f32,
+    pub max_offset: f32,
+    // in degrees
+    pub max_angle: f32,
+}
+
+impl Default for CameraShake {
+    fn default() -> Self {
+        CameraShake {
+            trauma: 0.0,
+            decay: 1.5,
+            max_offset: 24.0,
+            max_angle: 3.0,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ScreenFlash {
+    pub color: Color,
+    pub timer: Option<Timer>,
+}
+
+impl ScreenFlash {
+    pub fn active(&self) -> bool {
+        self.timer.as_ref().map_or(false, |timer| !timer.finished())
+    }
+}
+
+pub fn setup_flash_overlay(mut commands: Commands) {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                ..default()
+            },
+            color: UiColor(Color::NONE),
+            ..default()
+        })
+        .insert(FlashOverlay);
+}
+
+// Hitting something flashes the screen white and shakes it, once per hit
+// rather than every frame the bird stays in a pipe.
+pub fn collision_feedback(
+    flash: Res<ScreenFlash>,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut shake_events: EventWriter<CameraShakeEvent>,
+    mut flash_events: EventWriter<ScreenFlashEvent>,
+) {
+    if collision_events.iter().count() == 0 || flash.active() {
+        return;
+    }
+    shake_events.send(CameraShakeEvent(COLLISION_TRAUMA));
+    flash_events.send(ScreenFlashEvent {
+        color: Color::WHITE,
+        secs: COLLISION_FLASH_SECS,
+    });
+}
+
+pub fn start_camera_effects(
+    settings: Res<Settings>,
+    mut shake: ResMut<CameraShake>,
+    mut flash: ResMut<ScreenFlash>,
+    mut shake_events: EventReader<CameraShakeEvent>,
+    mut flash_events: EventReader<ScreenFlashEvent>,
+) {
+    let reduced_motion = settings.accessibility.reduced_motion;
+    for CameraShakeEvent(trauma) in shake_events.iter() {
+        if !reduced_motion {
+            shake.trauma = (shake.trauma + trauma).min(1.0);
+        }
+    }
+    for event in flash_events.iter() {
+        if !reduced_motion {
+            flash.color = event.color;
+            flash.timer = Some(Timer::from_seconds(event.secs, false));
+        }
+    }
+}
+
+pub fn shake_camera(
+    time: Res<Time>,
+    mut shake: ResMut<CameraShake>,
+    mut query: Query<&mut Transform, With<GameCamera>>,
+) {
+    if shake.trauma <= 0.0 {
+        return;
+    }
+    shake.trauma = (shake.trauma - shake.decay * time.delta_seconds()).max(0.0);
+
+    let mut rng = thread_rng();
+    let amount = shake.trauma * shake.trauma;
+    for mut transform in query.iter_mut() {
+        transform.translation.x = shake.max_offset * amount * rng.gen_range(-1.0..=1.0);
+        transform.translation.y = shake.max_offset * amount * rng.gen_range(-1.0..=1.0);
+        let angle = shake.max_angle * amount * rng.gen_range(-1.0..=1.0);
+        transform.rotation = Quat::from_rotation_z(angle.to_radians());
+    }
+}
+
+pub fn fade_flash(
+    time: Res<Time>,
+    mut flash: ResMut<ScreenFlash>,
+    mut query: Query<&mut UiColor, With<FlashOverlay>>,
+) {
+    let alpha = match &mut flash.timer {
+        Some(timer) => {
+            timer.tick(time.delta());
+            FLASH_ALPHA * timer.percent_left()
+        }
+        None => return,
+    };
+    let mut color = flash.color;
+    color.set_a(alpha);
+    for mut ui_color in query.iter_mut() {
+        ui_color.0 = color;
+    }
+    if alpha <= 0.0 {
+        flash.timer = None;
+    }
+}
+
+pub struct CameraEffectsPlugin;
+
+impl Plugin for CameraEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CameraShake>()
+            .init_resource::<ScreenFlash>()
+            .add_event::<CameraShakeEvent>()
+            .add_event::<ScreenFlashEvent>()
+            .add_startup_system(setup_flash_overlay)
+            .add_system(collision_feedback)
+            .add_system(start_camera_effects.after(collision_feedback))
+            .add_system(shake_camera.after(start_camera_effects))
+            .add_system(fade_flash.after(start_camera_effects));
+    }
+}
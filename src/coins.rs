@@ -0,0 +1,161 @@
+use crate::{
+    components::*, modes::GameMode, pipe_behaviors::PipeMotion, power_ups::ActivePowerUps,
+    save::SaveData, time_scale::running_set,
+};
+use bevy::{
+    math::{const_vec2, const_vec3, vec3},
+    prelude::*,
+    sprite::collide_aabb::collide,
+};
+
+// sprite is 16x16, drawn at twice the size
+pub const COIN: Vec2 = const_vec2!([32.0, 32.0]);
+pub const COIN_POSITION: Vec3 = const_vec3!([0.0, 0.0, 0.5]);
+
+// Sits in the gap of a pipe column and scrolls with it.
+#[derive(Component)]
+pub struct Coin {
+    pub collected: bool,
+}
+
+// coins picked up during the current run
+#[derive(Default)]
+pub struct CoinCounter(pub usize);
+
+#[derive(Component)]
+pub struct CoinText;
+
+// where a coin belongs in its column, following a moving gap
+fn coin_position(column: &Parent, motion_query: &Query<&PipeMotion>) -> Vec3 {
+    let gap_offset = motion_query
+        .get(column.0)
+        .map_or(0.0, |motion| motion.gap_offset);
+    COIN_POSITION + vec3(0.0, gap_offset, 0.0)
+}
+
+pub fn setup_coins(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    column_query: Query<Entity, (With<AutoMoving>, With<Countable>)>,
+) {
+    let coin_image = asset_server.load("sprites/coin.png");
+    for column in column_query.iter() {
+        let coin = commands
+            .spawn_bundle(SpriteBundle {
+                texture: coin_image.clone(),
+                transform: Transform {
+                    translation: COIN_POSITION,
+                    scale: vec3(2.0, 2.0, 1.0),
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(Coin { collected: false })
+            .id();
+        commands.entity(column).add_child(coin);
+    }
+}
+
+pub fn setup_coin_text(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let style = TextStyle {
+        font: asset_server.load("flappy-font.ttf"),
+        font_size: 40.0,
+        color: Color::rgb(0.99, 0.82, 0.22),
+    };
+    let alignment = TextAlignment {
+        vertical: VerticalAlign::Center,
+        horizontal: HorizontalAlign::Left,
+    };
+
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section("0", style, alignment),
+            transform: Transform::from_xyz(-SCREEN.x / 2.0 + 20.0, SCREEN.y / 2.0 - 40.0, 11.0),
+            ..default()
+        })
+        .insert(CoinText);
+}
+
+pub fn reset_coins(mut counter: ResMut<CoinCounter>) {
+    counter.0 = 0;
+}
+
+pub fn collect_coins(
+    mut counter: ResMut<CoinCounter>,
+    power_ups: Res<ActivePowerUps>,
+    player_query: Query<&GlobalTransform, With<Collider>>,
+    mut coin_query: Query<(&mut Coin, &mut Visibility, &GlobalTransform)>,
+) {
+    for player_transform in player_query.iter() {
+        for (mut coin, mut visibility, coin_transform) in coin_query.iter_mut() {
+            if coin.collected {
+                continue;
+            }
+
+            let hit = collide(
+                player_transform.translation,
+                power_ups.player_hitbox(),
+                coin_transform.translation,
+                COIN,
+            );
+            if hit.is_some() {
+                coin.collected = true;
+                visibility.is_visible = false;
+                counter.0 += 1;
+            }
+        }
+    }
+}
+
+// Puts the coin of a column back whenever the column is laid out again.
+pub fn place_coins(
+    mut column_events: EventReader<ColumnPlacedEvent>,
+    motion_query: Query<&PipeMotion>,
+    mut query: Query<(&mut Coin, &mut Transform, &mut Visibility, &Parent)>,
+) {
+    for placed in column_events.iter() {
+        for (mut coin, mut transform, mut visibility, column) in query.iter_mut() {
+            if column.0 != placed.column {
+                continue;
+            }
+            // a column without a coin keeps it hidden as if collected
+            coin.collected = !placed.coin;
+            transform.translation = coin_position(column, &motion_query);
+            visibility.is_visible = placed.coin;
+        }
+    }
+}
+
+pub fn update_coin_text(counter: Res<CoinCounter>, mut query: Query<&mut Text, With<CoinText>>) {
+    if !counter.is_changed() {
+        return;
+    }
+
+    for mut text in query.iter_mut() {
+        text.sections[0].value = counter.0.to_string();
+    }
+}
+
+pub fn bank_coins(mode: Res<GameMode>, counter: Res<CoinCounter>, mut save_data: ResMut<SaveData>) {
+    // practice runs are free to repeat, so they do not pay out
+    if mode.records_high_score() && counter.0 > 0 {
+        save_data.wallet += counter.0;
+        save_data.save();
+    }
+}
+
+pub struct CoinsPlugin;
+
+impl Plugin for CoinsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CoinCounter>()
+            .add_startup_system(setup_coin_text)
+            .add_startup_system_to_stage(StartupStage::PostStartup, setup_coins)
+            .add_system_set(SystemSet::on_enter(GameState::Paused).with_system(reset_coins))
+            .add_system_set(running_set().with_system(collect_coins))
+            .add_system(update_coin_text)
+            // after the course systems in `Update` have laid the columns out
+            .add_system_to_stage(CoreStage::PostUpdate, place_coins)
+            .add_system_set(SystemSet::on_enter(GameState::GameOver).with_system(bank_coins));
+    }
+}
@@ -2,6 +2,7 @@ use bevy::{
     math::{const_vec2, const_vec3},
     prelude::*,
 };
+use serde::Serialize;
 
 pub const TIME_STEP: f32 = 1.0 / 60.0;
 pub const GAME_WIDTH: f32 = 136.0;
@@ -16,15 +17,20 @@ pub const PIPE_WIDTH: f32 = 52.0;
 pub const PIPE_HEIGHT: f32 = 320.0;
 pub const FLOOR_WIDTH: f32 = 336.0;
 pub const FLOOR_HEIGHT: f32 = 112.0;
+pub const BACKGROUND_WIDTH: f32 = 288.0;
 
-// speed
-pub const AUTO_MOVE_SPEED: f32 = 1.0 * PIXELS_PER_METER;
+// speed, tuned per TIME_STEP frame and expressed per second
+pub const AUTO_MOVE_SPEED: f32 = 1.0 * PIXELS_PER_METER / TIME_STEP;
 pub const JUMP_SPEED: f32 = 100.0 * PIXELS_PER_METER;
-pub const SCALED_GRAVITY: f32 = -9.81 * PIXELS_PER_METER * 1.0;
+pub const SCALED_GRAVITY: f32 = -9.81 * PIXELS_PER_METER / TIME_STEP;
+pub const MAX_FALL_SPEED: f32 = 1000.0;
+pub const MAX_RISE_SPEED: f32 = 10000.0;
 
 // pos vals
 pub const FLOOR_POS: f32 = -112.0 * 4.0;
 pub const PLAYER_POS_X: f32 = -75.0;
+// lowest the bird gets, resting on the floor
+pub const PLAYER_MIN_Y: f32 = FLOOR_POS + 90.0;
 pub const PIPE_START_X: f32 = SCREEN_WIDTH + PIPE_WIDTH;
 
 //fonts
@@ -42,22 +48,33 @@ pub const SPACE_BETWEEN_PIPES: f32 = 80.0 * PIXELS_PER_METER;
 pub const VERTICAL_SPACE_BETWEEN_PIPES: f32 = PLAYER_HEIGHT * 3.5;
 pub const PIPE_OPENING_Y_POS_FACTOR: f32 = 30.0 * PIXELS_PER_METER;
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum GameState {
+    MainMenu,
+    Settings,
+    Stats,
+    Achievements,
     Paused,
     Running,
+    // hit a pipe, falling to the floor before the game is over
+    Dying,
     GameOver,
+    LevelComplete,
+    Editor,
 }
 pub struct Scoreboard {
     pub score: usize,
 }
 
-// in m/s - usually -9.8 m/s
+// in m/s² - usually -9.8 m/s²
 pub struct Gravity(pub f32);
 
 #[derive(Component)]
 pub struct Pipe;
 
+#[derive(Component)]
+pub struct Background;
+
 #[derive(Component)]
 pub struct Countable(pub bool);
 
@@ -70,12 +87,49 @@ pub struct GameOverUIInputTimer(pub Timer);
 #[derive(Component)]
 pub struct GameOverUI;
 
+#[derive(Component)]
+pub struct ReadyUI;
+
 #[derive(Component)]
 pub struct Collider;
 #[derive(Component)]
 pub struct Blocker(pub Vec2);
 
-pub struct CollisionEvent;
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Obstacle {
+    Pipe,
+    Floor,
+    Hazard,
+}
+
+pub struct CollisionEvent {
+    pub obstacle: Obstacle,
+}
+
+pub struct FlapEvent;
+
+// Sent for each column the bird gets past.
+pub struct PointScoredEvent {
+    pub column: Entity,
+}
+
+// Sent whenever a pipe column is laid out, at the start of a run and each
+// time it is moved back to the right edge of the course.
+pub struct ColumnPlacedEvent {
+    pub column: Entity,
+    pub x: f32,
+    pub y: f32,
+    // distance to the column before it
+    pub spacing: f32,
+    pub coin: bool,
+    // name of a power-up to put before the column, rolled at random if None
+    // outside of levels
+    pub power_up: Option<String>,
+}
+
+// Ignored by `collision_system` and blinking until the timer runs out.
+#[derive(Component)]
+pub struct Invulnerable(pub Timer);
 
 #[derive(Component)]
 pub struct Floor;
@@ -0,0 +1,29 @@
+use crate::{components::*, time_scale::WorldTime, transitions::Transition};
+use bevy::prelude::*;
+
+// degrees per second the bird turns its nose down while falling
+pub const NOSE_DIVE_SPEED: f32 = 720.0;
+
+// The bird stops flapping: whatever lift it had is gone. Scrolling, input and
+// the wing animation stop with the `Running` systems.
+pub fn start_dying(mut query: Query<&mut Player>) {
+    for mut player in query.iter_mut() {
+        player.movement_speed = player.movement_speed.min(0.0);
+    }
+}
+
+pub fn nose_dive_system(time: Res<WorldTime>, mut query: Query<&mut Player>) {
+    for mut player in query.iter_mut() {
+        player.angle = (player.angle - NOSE_DIVE_SPEED * time.delta_seconds()).max(-90.0);
+    }
+}
+
+// The game is over once the bird hits the floor.
+pub fn land_system(mut transition: ResMut<Transition>, query: Query<&Transform, With<Player>>) {
+    let landed = query
+        .iter()
+        .any(|transform| transform.translation.y <= PLAYER_MIN_Y);
+    if landed {
+        transition.fade_to(GameState::GameOver);
+    }
+}
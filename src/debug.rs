@@ -0,0 +1,178 @@
+use crate::{components::*, particles::Particle, power_ups::ActivePowerUps};
+use bevy::{
+    diagnostic::{Diagnostics, FrameTimeDiagnosticsPlugin},
+    math::vec3,
+    prelude::*,
+};
+
+pub const DEBUG_KEY: KeyCode = KeyCode::F3;
+// in front of everything in the world
+const HITBOX_Z: f32 = 50.0;
+const HITBOX_LINE: f32 = 2.0;
+const PLAYER_HITBOX_COLOR: Color = Color::rgb(0.2, 1.0, 0.2);
+const BLOCKER_HITBOX_COLOR: Color = Color::rgb(1.0, 0.2, 0.2);
+
+#[derive(Default)]
+pub struct DebugOverlay {
+    pub visible: bool,
+}
+
+#[derive(Component)]
+pub struct DebugText;
+
+// Outlines are redrawn every frame, these are last frame's.
+#[derive(Component)]
+pub struct DebugHitbox;
+
+pub fn setup_debug_text(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    left: Val::Px(8.0),
+                    top: Val::Px(8.0),
+                    ..default()
+                },
+                ..default()
+            },
+            text: Text::with_section(
+                "",
+                TextStyle {
+                    font: asset_server.load("flappy-font.ttf"),
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                },
+                default(),
+            ),
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(DebugText);
+}
+
+pub fn toggle_debug_overlay(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut overlay: ResMut<DebugOverlay>,
+    mut query: Query<&mut Visibility, With<DebugText>>,
+) {
+    if !keyboard_input.just_pressed(DEBUG_KEY) {
+        return;
+    }
+    overlay.visible = !overlay.visible;
+    for mut visibility in query.iter_mut() {
+        visibility.is_visible = overlay.visible;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_debug_text(
+    overlay: Res<DebugOverlay>,
+    game_state: Res<State<GameState>>,
+    diagnostics: Res<Diagnostics>,
+    player_query: Query<&Player>,
+    entity_query: Query<Entity>,
+    blocker_query: Query<&Blocker>,
+    particle_query: Query<&Particle>,
+    mut text_query: Query<&mut Text, With<DebugText>>,
+) {
+    if !overlay.visible {
+        return;
+    }
+
+    let fps = diagnostics
+        .get(FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|fps| fps.average())
+        .unwrap_or(0.0);
+    let (speed, angle) = player_query
+        .get_single()
+        .map_or((0.0, 0.0), |player| (player.movement_speed, player.angle));
+    let value = format!(
+        "STATE {:?}\nFPS {:.0}\nVELOCITY {:.0}\nANGLE {:.0}\nENTITIES {}\nBLOCKERS {}\nPARTICLES {}",
+        game_state.current(),
+        fps,
+        speed,
+        angle,
+        entity_query.iter().count(),
+        blocker_query.iter().count(),
+        particle_query.iter().count(),
+    );
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = value.clone();
+    }
+}
+
+fn spawn_outline(commands: &mut Commands, center: Vec3, size: Vec2, color: Color) {
+    let edges = [
+        (vec3(0.0, size.y / 2.0, 0.0), Vec2::new(size.x, HITBOX_LINE)),
+        (
+            vec3(0.0, -size.y / 2.0, 0.0),
+            Vec2::new(size.x, HITBOX_LINE),
+        ),
+        (vec3(size.x / 2.0, 0.0, 0.0), Vec2::new(HITBOX_LINE, size.y)),
+        (
+            vec3(-size.x / 2.0, 0.0, 0.0),
+            Vec2::new(HITBOX_LINE, size.y),
+        ),
+    ];
+    for (offset, edge) in edges {
+        commands
+            .spawn_bundle(SpriteBundle {
+                sprite: Sprite {
+                    color,
+                    custom_size: Some(edge),
+                    ..default()
+                },
+                transform: Transform::from_translation(vec3(center.x, center.y, HITBOX_Z) + offset),
+                ..default()
+            })
+            .insert(DebugHitbox);
+    }
+}
+
+// Draws the boxes `collision_system` tests against each other.
+pub fn draw_hitboxes(
+    mut commands: Commands,
+    overlay: Res<DebugOverlay>,
+    power_ups: Res<ActivePowerUps>,
+    drawn_query: Query<Entity, With<DebugHitbox>>,
+    collider_query: Query<&GlobalTransform, With<Collider>>,
+    blocker_query: Query<(&Blocker, &GlobalTransform)>,
+) {
+    for entity in drawn_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    if !overlay.visible {
+        return;
+    }
+
+    for transform in collider_query.iter() {
+        spawn_outline(
+            &mut commands,
+            transform.translation,
+            power_ups.player_hitbox(),
+            PLAYER_HITBOX_COLOR,
+        );
+    }
+    for (blocker, transform) in blocker_query.iter() {
+        spawn_outline(
+            &mut commands,
+            transform.translation,
+            blocker.0,
+            BLOCKER_HITBOX_COLOR,
+        );
+    }
+}
+
+pub struct DebugPlugin;
+
+impl Plugin for DebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DebugOverlay>()
+            .add_plugin(FrameTimeDiagnosticsPlugin)
+            .add_startup_system(setup_debug_text)
+            .add_system(toggle_debug_overlay)
+            .add_system(update_debug_text.after(toggle_debug_overlay))
+            .add_system(draw_hitboxes.after(toggle_debug_overlay));
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{
+    components::GameState,
+    time_scale::{update_world_time, GameSpeed},
+};
+use bevy::{
+    ecs::schedule::{RunCriteriaDescriptor, RunCriteriaDescriptorCoercion, ShouldRun},
+    prelude::*,
+};
+
+pub const FREEZE_KEY: KeyCode = KeyCode::F5;
+pub const STEP_KEY: KeyCode = KeyCode::F6;
+pub const SPEED_KEY: KeyCode = KeyCode::F7;
+pub const DEV_SPEEDS: [f32; 3] = [1.0, 2.0, 4.0];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, RunCriteriaLabel)]
+struct RunningCriteria;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, RunCriteriaLabel)]
+struct DevTimeCriteria;
+
+// Developer time controls, only compiled in with the `dev` feature.
+#[derive(Default)]
+pub struct DevTime {
+    pub frozen: bool,
+    // run the `Running` systems once while frozen
+    pub step: bool,
+    // index into DEV_SPEEDS
+    pub speed: usize,
+}
+
+// Gates the `Running` systems on top of the state: nothing runs while frozen
+// unless a step was asked for.
+pub fn running_criteria() -> RunCriteriaDescriptor {
+    RunCriteria::pipe(RunningCriteria, gate_running).label_discard_if_duplicate(DevTimeCriteria)
+}
+
+fn gate_running(In(should_run): In<ShouldRun>, mut dev_time: ResMut<DevTime>) -> ShouldRun {
+    let running = matches!(should_run, ShouldRun::Yes | ShouldRun::YesAndCheckAgain);
+    if !dev_time.frozen || !running {
+        return should_run;
+    }
+    if dev_time.step {
+        dev_time.step = false;
+        return should_run;
+    }
+    ShouldRun::No
+}
+
+pub fn dev_time_keys(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut dev_time: ResMut<DevTime>,
+    mut game_speed: ResMut<GameSpeed>,
+) {
+    if keyboard_input.just_pressed(FREEZE_KEY) {
+        dev_time.frozen = !dev_time.frozen;
+        dev_time.step = false;
+        info!(
+            "time {}",
+            if dev_time.frozen { "frozen" } else { "running" }
+        );
+    }
+    if keyboard_input.just_pressed(STEP_KEY) && dev_time.frozen {
+        dev_time.step = true;
+    }
+    if keyboard_input.just_pressed(SPEED_KEY) {
+        dev_time.speed = (dev_time.speed + 1) % DEV_SPEEDS.len();
+        info!("time at {}x", DEV_SPEEDS[dev_time.speed]);
+    }
+    if game_speed.dev != DEV_SPEEDS[dev_time.speed] {
+        game_speed.dev = DEV_SPEEDS[dev_time.speed];
+    }
+}
+
+pub struct DevTimePlugin;
+
+impl Plugin for DevTimePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DevTime>()
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                dev_time_keys.before(update_world_time),
+            )
+            .stage(CoreStage::Update, |stage: &mut SystemStage| {
+                stage.add_system_run_criteria(
+                    State::<GameState>::on_update(GameState::Running)
+                        .label_discard_if_duplicate(RunningCriteria),
+                )
+            });
+    }
+}
@@ -0,0 +1,738 @@
+use crate::{
+    components::*,
+    hazards::Hazard,
+    input::back_just_pressed,
+    levels::{
+        CurrentLevel, Level, LevelColumn, DEFAULT_LEVEL, LEVEL_START_X, MIN_LEVEL_SPACING,
+        PARKED_COLUMN_X,
+    },
+    locale::Localization,
+    modes::GameMode,
+    pipe_behaviors::{pipe_translation_y, PipeBehavior},
+    power_ups::{PowerUpDefinitions, PowerUpIcons},
+    save::SaveData,
+    settings::Settings,
+};
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    math::{vec2, vec3},
+    prelude::*,
+};
+
+pub const EDITOR_SCROLL_SPEED: f32 = 600.0;
+// pixels scrolled per line of a mouse wheel
+pub const WHEEL_LINE: f32 = 60.0;
+pub const MIN_GAP_SIZE: f32 = PLAYER_HEIGHT * 1.5;
+pub const MAX_GAP_SIZE: f32 = PLAYER_HEIGHT * 8.0;
+pub const MAX_GAP_Y: f32 = 360.0;
+// presses this close to a gap edge resize the gap instead of moving the column
+const GAP_EDGE_GRAB: f32 = 24.0;
+const SELECTED_COLOR: Color = Color::rgb(1.0, 0.7, 0.35);
+const MESSAGE_SECS: f32 = 2.0;
+const EDITOR_FONT_SIZE: f32 = 24.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Drag {
+    // offset of the press from the gap center
+    Move(Vec2),
+    Resize,
+}
+
+// The level being edited. It stays around while play-testing so the editor
+// comes back to the same spot.
+#[derive(Default)]
+pub struct Editor {
+    pub level: Option<Level>,
+    // course x at the center of the screen
+    pub scroll: f32,
+    pub selected: Option<usize>,
+    drag: Option<Drag>,
+    message: Option<(String, Timer)>,
+    // what the preview was last drawn from, the editor is mutably borrowed
+    // every frame so change detection can't tell
+    drawn: Option<(Vec<LevelColumn>, f32, Option<usize>)>,
+}
+
+impl Editor {
+    fn show_message(&mut self, message: &str) {
+        self.message = Some((
+            message.to_string(),
+            Timer::from_seconds(MESSAGE_SECS, false),
+        ));
+    }
+}
+
+// Everything drawn for the edited level, rebuilt whenever it changes.
+#[derive(Component)]
+pub struct EditorPreview;
+
+#[derive(Component)]
+pub struct EditorUi;
+
+#[derive(Component)]
+pub struct EditorStatusText;
+
+fn behavior_key(behavior: PipeBehavior) -> &'static str {
+    match behavior {
+        PipeBehavior::Static => "behavior-static",
+        PipeBehavior::Oscillating => "behavior-oscillating",
+        PipeBehavior::Breathing => "behavior-breathing",
+        PipeBehavior::DropIn => "behavior-drop-in",
+    }
+}
+
+fn cursor_position(windows: &Windows) -> Option<Vec2> {
+    let window = windows.get_primary()?;
+    let position = window.cursor_position()?;
+    Some(position - vec2(window.width(), window.height()) / 2.0)
+}
+
+// index of the column under the cursor and what a press there would do
+fn hit_column(level: &Level, scroll: f32, cursor: Vec2) -> Option<(usize, Drag)> {
+    let xs = level.column_xs();
+    level
+        .columns
+        .iter()
+        .enumerate()
+        .find_map(|(index, column)| {
+            let offset = vec2(cursor.x + scroll - xs[index], cursor.y - column.gap_y);
+            if offset.x.abs() > PIPE.x / 2.0 {
+                return None;
+            }
+            if (offset.y.abs() - column.gap_size / 2.0).abs() <= GAP_EDGE_GRAB {
+                Some((index, Drag::Resize))
+            } else {
+                Some((index, Drag::Move(offset)))
+            }
+        })
+}
+
+fn previous_x(xs: &[f32], index: usize) -> f32 {
+    if index == 0 {
+        LEVEL_START_X
+    } else {
+        xs[index - 1]
+    }
+}
+
+// Adds a column at course x, keeping the others where they are. Returns its
+// index, None if there is no room.
+fn insert_column(level: &mut Level, x: f32, gap_y: f32) -> Option<usize> {
+    let xs = level.column_xs();
+    let index = xs.iter().position(|other| *other > x).unwrap_or(xs.len());
+    let previous = previous_x(&xs, index);
+    let next = xs.get(index).copied();
+    if x - previous < MIN_LEVEL_SPACING || next.map_or(false, |next| next - x < MIN_LEVEL_SPACING) {
+        return None;
+    }
+
+    if let Some(next) = next {
+        level.columns[index].spacing = next - x;
+    }
+    level.columns.insert(
+        index,
+        LevelColumn {
+            gap_y,
+            spacing: x - previous,
+            ..default()
+        },
+    );
+    Some(index)
+}
+
+fn remove_column(level: &mut Level, index: usize) {
+    let xs = level.column_xs();
+    if index + 1 < xs.len() {
+        level.columns[index + 1].spacing = xs[index + 1] - previous_x(&xs, index);
+    }
+    level.columns.remove(index);
+}
+
+// Moves a column to course x, between its neighbours.
+fn move_column(level: &mut Level, index: usize, x: f32, gap_y: f32) {
+    let xs = level.column_xs();
+    let previous = previous_x(&xs, index);
+    let mut x = x.max(previous + MIN_LEVEL_SPACING);
+    if let Some(next) = xs.get(index + 1) {
+        x = x.min(next - MIN_LEVEL_SPACING);
+        level.columns[index + 1].spacing = next - x;
+    }
+    level.columns[index].spacing = x - previous;
+    level.columns[index].gap_y = gap_y.clamp(-MAX_GAP_Y, MAX_GAP_Y);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_level(path: &str, level: &Level) -> bool {
+    let raw = match ron::ser::to_string_pretty(level, ron::ser::PrettyConfig::default()) {
+        Ok(raw) => raw,
+        Err(err) => {
+            warn!("could not serialize {}: {}", path, err);
+            return false;
+        }
+    };
+    match std::fs::write(std::path::Path::new("assets").join(path), raw) {
+        Ok(()) => true,
+        Err(err) => {
+            warn!("could not write {}: {}", path, err);
+            false
+        }
+    }
+}
+
+// the web build can't write its assets
+#[cfg(target_arch = "wasm32")]
+fn write_level(path: &str, _level: &Level) -> bool {
+    warn!(
+        "levels can only be saved on desktop, {} was not written",
+        path
+    );
+    false
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn enter_editor(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    localization: Res<Localization>,
+    levels: Res<Assets<Level>>,
+    current_level: Res<CurrentLevel>,
+    mut editor: ResMut<Editor>,
+    mut mouse_button_input: ResMut<Input<MouseButton>>,
+    mut column_query: Query<&mut Transform, With<AutoMoving>>,
+    mut player_query: Query<&mut Visibility, With<Player>>,
+    hazard_query: Query<Entity, With<Hazard>>,
+) {
+    if editor.level.is_none() {
+        editor.level = Some(match levels.get(&current_level.handle) {
+            Some(level) => level.clone(),
+            None => {
+                warn!("level is not loaded, starting an empty one");
+                Level::default()
+            }
+        });
+    }
+    editor.drag = None;
+    editor.drawn = None;
+    // the click that opened the editor shouldn't add a column
+    mouse_button_input.reset(MouseButton::Left);
+
+    // the editor draws its own columns, keep the course out of the way
+    for mut transform in column_query.iter_mut() {
+        transform.translation.x = PARKED_COLUMN_X;
+    }
+    for mut visibility in player_query.iter_mut() {
+        visibility.is_visible = false;
+    }
+    for entity in hazard_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    let font = localization.font(&asset_server);
+    let style = TextStyle {
+        font,
+        font_size: EDITOR_FONT_SIZE,
+        color: Color::WHITE,
+    };
+    let alignment = TextAlignment {
+        vertical: VerticalAlign::Center,
+        horizontal: HorizontalAlign::Left,
+    };
+    let help = ["editor-help-scroll", "editor-help-edit", "editor-help-keys"];
+    for (line, key) in help.iter().enumerate() {
+        commands
+            .spawn_bundle(Text2dBundle {
+                text: Text::with_section(localization.get(key), style.clone(), alignment),
+                transform: Transform::from_xyz(
+                    -SCREEN.x / 2.0 + 12.0,
+                    SCREEN.y / 2.0 - 24.0 - line as f32 * 28.0,
+                    12.0,
+                ),
+                ..default()
+            })
+            .insert(EditorUi);
+    }
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section("", style, alignment),
+            transform: Transform::from_xyz(-SCREEN.x / 2.0 + 12.0, -SCREEN.y / 2.0 + 24.0, 12.0),
+            ..default()
+        })
+        .insert(EditorUi)
+        .insert(EditorStatusText);
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn editor_input(
+    mut game_state: ResMut<State<GameState>>,
+    mut mode: ResMut<GameMode>,
+    mut editor: ResMut<Editor>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut levels: ResMut<Assets<Level>>,
+    asset_server: Res<AssetServer>,
+    localization: Res<Localization>,
+    definitions: Res<PowerUpDefinitions>,
+    time: Res<Time>,
+    windows: Res<Windows>,
+    mut keyboard_input: ResMut<Input<KeyCode>>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    gamepads: Res<Gamepads>,
+    gamepad_input: Res<Input<GamepadButton>>,
+    mut wheel_events: EventReader<MouseWheel>,
+) {
+    if back_just_pressed(&keyboard_input, &gamepads, &gamepad_input) {
+        // the main menu would otherwise read the same press as "back"
+        keyboard_input.reset(KeyCode::Escape);
+        game_state.set(GameState::MainMenu).unwrap();
+        return;
+    }
+
+    let editor = &mut *editor;
+    if let Some((_, timer)) = &mut editor.message {
+        if timer.tick(time.delta()).finished() {
+            editor.message = None;
+        }
+    }
+    let level = match &mut editor.level {
+        Some(level) => level,
+        None => return,
+    };
+    let mut message = None;
+
+    // scrolling
+    let mut scroll = editor.scroll;
+    for event in wheel_events.iter() {
+        let lines = event.x + event.y;
+        scroll -= match event.unit {
+            MouseScrollUnit::Line => lines * WHEEL_LINE,
+            MouseScrollUnit::Pixel => lines,
+        };
+    }
+    if keyboard_input.pressed(KeyCode::Left) {
+        scroll -= EDITOR_SCROLL_SPEED * time.delta_seconds();
+    }
+    if keyboard_input.pressed(KeyCode::Right) {
+        scroll += EDITOR_SCROLL_SPEED * time.delta_seconds();
+    }
+    let end = level.column_xs().last().copied().unwrap_or(0.0);
+    scroll = scroll.clamp(0.0, end.max(0.0));
+    editor.scroll = scroll;
+
+    // placing, dragging and deleting columns
+    if let Some(cursor) = cursor_position(&windows) {
+        if mouse_button_input.just_pressed(MouseButton::Left) {
+            match hit_column(level, scroll, cursor) {
+                Some((index, drag)) => {
+                    editor.selected = Some(index);
+                    editor.drag = Some(drag);
+                }
+                None => match insert_column(level, cursor.x + scroll, cursor.y) {
+                    Some(index) => {
+                        editor.selected = Some(index);
+                        editor.drag = Some(Drag::Move(Vec2::ZERO));
+                    }
+                    None => message = Some(localization.get("editor-too-close")),
+                },
+            }
+        } else if mouse_button_input.pressed(MouseButton::Left) {
+            match (editor.drag, editor.selected) {
+                (Some(Drag::Move(grab)), Some(index)) => {
+                    let target = cursor - grab;
+                    move_column(level, index, target.x + scroll, target.y);
+                }
+                (Some(Drag::Resize), Some(index)) => {
+                    let column = &mut level.columns[index];
+                    let size = 2.0 * (cursor.y - column.gap_y).abs();
+                    column.gap_size = size.clamp(MIN_GAP_SIZE, MAX_GAP_SIZE);
+                }
+                _ => {}
+            }
+        } else {
+            editor.drag = None;
+        }
+
+        if mouse_button_input.just_pressed(MouseButton::Right) {
+            if let Some((index, _)) = hit_column(level, scroll, cursor) {
+                remove_column(level, index);
+                editor.selected = None;
+            }
+        }
+    }
+
+    // editing the selected column
+    if let Some((index, column)) = editor
+        .selected
+        .and_then(|index| level.columns.get_mut(index).map(|column| (index, column)))
+    {
+        if keyboard_input.just_pressed(KeyCode::B) {
+            let next = PipeBehavior::MOVING
+                .iter()
+                .skip_while(|behavior| **behavior != column.behavior)
+                .nth(1);
+            column.behavior = match (column.behavior, next) {
+                (PipeBehavior::Static, _) => PipeBehavior::MOVING[0],
+                (_, Some(next)) => *next,
+                (_, None) => PipeBehavior::Static,
+            };
+        }
+        if keyboard_input.just_pressed(KeyCode::C) {
+            column.coin = !column.coin;
+        }
+        if keyboard_input.just_pressed(KeyCode::P) {
+            let names: Vec<&String> = definitions.power_ups.iter().map(|d| &d.name).collect();
+            column.power_up = match &column.power_up {
+                None => names.first().map(|name| name.to_string()),
+                Some(name) => {
+                    let index = names.iter().position(|other| *other == name);
+                    index
+                        .and_then(|index| names.get(index + 1))
+                        .map(|name| name.to_string())
+                }
+            };
+        }
+        if keyboard_input.just_pressed(KeyCode::Delete) {
+            remove_column(level, index);
+            editor.selected = None;
+        }
+    }
+
+    // play-testing from the first column ahead of the bird
+    if keyboard_input.just_pressed(KeyCode::T) {
+        let xs = level.column_xs();
+        match xs.iter().position(|x| x - scroll > PLAYER_POS_X) {
+            Some(start) => {
+                let playtest = Level {
+                    name: level.name.clone(),
+                    columns: level.columns[start..].to_vec(),
+                };
+                levels.set_untracked(current_level.playtest.clone_weak(), playtest);
+                current_level.next = 0;
+                *mode = GameMode::Playtest;
+                game_state.set(GameState::Paused).unwrap();
+            }
+            None => message = Some(localization.get("editor-nothing-to-test")),
+        }
+    }
+
+    if keyboard_input.just_pressed(KeyCode::S) {
+        let path = asset_server
+            .get_handle_path(&current_level.handle)
+            .map(|path| path.path().to_string_lossy().into_owned())
+            .unwrap_or_else(|| DEFAULT_LEVEL.to_string());
+        levels.set_untracked(current_level.handle.clone_weak(), level.clone());
+        if write_level(&path, level) {
+            message = Some(localization.format("editor-saved", &[("path", path.to_uppercase())]));
+        } else {
+            message = Some(localization.get("editor-save-failed"));
+        }
+    }
+
+    if let Some(message) = message {
+        editor.show_message(&message);
+    }
+}
+
+// Lays the edited level out with the same sprites and sizes the game uses.
+#[allow(clippy::too_many_arguments)]
+pub fn draw_editor(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    localization: Res<Localization>,
+    settings: Res<Settings>,
+    save_data: Res<SaveData>,
+    icons: Res<PowerUpIcons>,
+    definitions: Res<PowerUpDefinitions>,
+    mut editor: ResMut<Editor>,
+    preview_query: Query<Entity, With<EditorPreview>>,
+) {
+    let level = match &editor.level {
+        Some(level) => level,
+        None => return,
+    };
+    let drawn = Some((level.columns.clone(), editor.scroll, editor.selected));
+    if editor.drawn == drawn {
+        return;
+    }
+    for entity in preview_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let pipe_image = asset_server.load(save_data.theme_or_default(settings.theme).pipe());
+    let coin_image = asset_server.load("sprites/coin.png");
+    let font = localization.font(&asset_server);
+    let xs = level.column_xs();
+
+    for (index, column) in level.columns.iter().enumerate() {
+        let x = xs[index] - editor.scroll;
+        // the power-up sits half a spacing before the column
+        if x + PIPE.x < -SCREEN.x / 2.0 || x - column.spacing / 2.0 > SCREEN.x / 2.0 + PIPE.x {
+            continue;
+        }
+        let color = if editor.selected == Some(index) {
+            SELECTED_COLOR
+        } else {
+            Color::WHITE
+        };
+
+        commands
+            .spawn_bundle(TransformBundle::from_transform(Transform::from_xyz(
+                x,
+                column.gap_y,
+                1.0,
+            )))
+            .insert(EditorPreview)
+            .with_children(|parent| {
+                for half in [1.0, -1.0] {
+                    let rotation = if half > 0.0 {
+                        Quat::from_rotation_z(180.0_f32.to_radians())
+                    } else {
+                        Quat::IDENTITY
+                    };
+                    parent.spawn_bundle(SpriteBundle {
+                        texture: pipe_image.clone(),
+                        sprite: Sprite { color, ..default() },
+                        transform: Transform {
+                            translation: vec3(0.0, pipe_translation_y(half, column.gap_size), 0.0),
+                            scale: vec3(2.0, 2.0, 1.0),
+                            rotation,
+                        },
+                        ..default()
+                    });
+                }
+                if column.coin {
+                    parent.spawn_bundle(SpriteBundle {
+                        texture: coin_image.clone(),
+                        transform: Transform {
+                            translation: vec3(0.0, 0.0, 0.5),
+                            scale: vec3(2.0, 2.0, 1.0),
+                            ..default()
+                        },
+                        ..default()
+                    });
+                }
+                let power_up = column
+                    .power_up
+                    .as_ref()
+                    .and_then(|name| definitions.power_ups.iter().position(|d| &d.name == name));
+                if let Some(power_up) = power_up {
+                    parent.spawn_bundle(SpriteBundle {
+                        texture: icons.0[power_up].clone(),
+                        transform: Transform {
+                            translation: vec3(-column.spacing / 2.0, 0.0, 0.5),
+                            scale: vec3(2.0, 2.0, 1.0),
+                            ..default()
+                        },
+                        ..default()
+                    });
+                }
+            });
+
+        if column.behavior != PipeBehavior::Static {
+            commands
+                .spawn_bundle(Text2dBundle {
+                    text: Text::with_section(
+                        localization.get(behavior_key(column.behavior)),
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: EDITOR_FONT_SIZE,
+                            color,
+                        },
+                        TextAlignment {
+                            vertical: VerticalAlign::Center,
+                            horizontal: HorizontalAlign::Center,
+                        },
+                    ),
+                    transform: Transform::from_xyz(x, SCREEN.y / 2.0 - 120.0, 12.0),
+                    ..default()
+                })
+                .insert(EditorPreview);
+        }
+    }
+    editor.drawn = drawn;
+}
+
+pub fn update_editor_status(
+    localization: Res<Localization>,
+    editor: Res<Editor>,
+    mut query: Query<&mut Text, With<EditorStatusText>>,
+) {
+    let level = match &editor.level {
+        Some(level) => level,
+        None => return,
+    };
+
+    let status = match (&editor.message, editor.selected) {
+        (Some((message, _)), _) => message.clone(),
+        (None, Some(index)) if index < level.columns.len() => {
+            let column = &level.columns[index];
+            let coin = if column.coin {
+                "settings-on"
+            } else {
+                "settings-off"
+            };
+            let power_up = match &column.power_up {
+                Some(name) => name.clone(),
+                None => localization.get("editor-none"),
+            };
+            localization.format(
+                "editor-status",
+                &[
+                    ("column", (index + 1).to_string()),
+                    ("count", level.columns.len().to_string()),
+                    ("behavior", localization.get(behavior_key(column.behavior))),
+                    ("coin", localization.get(coin)),
+                    ("power-up", power_up),
+                ],
+            )
+        }
+        _ => localization.format(
+            "editor-level",
+            &[
+                ("name", level.name.clone()),
+                ("count", level.columns.len().to_string()),
+            ],
+        ),
+    };
+    for mut text in query.iter_mut() {
+        if text.sections[0].value != status {
+            text.sections[0].value = status.clone();
+        }
+    }
+}
+
+// Leaving a play-test goes back to the editor instead of the main menu.
+pub fn return_to_editor(mut mode: ResMut<GameMode>, mut game_state: ResMut<State<GameState>>) {
+    if *mode == GameMode::Playtest {
+        *mode = GameMode::Level;
+        game_state.set(GameState::Editor).unwrap();
+    }
+}
+
+pub fn exit_editor(
+    mut commands: Commands,
+    preview_query: Query<Entity, With<EditorPreview>>,
+    ui_query: Query<Entity, With<EditorUi>>,
+) {
+    for entity in preview_query.iter().chain(ui_query.iter()) {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Editor>()
+            .add_system_set(SystemSet::on_enter(GameState::Editor).with_system(enter_editor))
+            .add_system_set(
+                SystemSet::on_update(GameState::Editor)
+                    .with_system(editor_input)
+                    .with_system(draw_editor.after(editor_input))
+                    .with_system(update_editor_status.after(editor_input)),
+            )
+            .add_system_set(SystemSet::on_exit(GameState::Editor).with_system(exit_editor))
+            .add_system_set(SystemSet::on_enter(GameState::MainMenu).with_system(return_to_editor));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level(spacings: &[f32]) -> Level {
+        Level {
+            columns: spacings
+                .iter()
+                .map(|spacing| LevelColumn {
+                    spacing: *spacing,
+                    ..default()
+                })
+                .collect(),
+            ..default()
+        }
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 0.01, "{} != {}", a, b);
+    }
+
+    // every spacing is what the column is actually placed at, and no closer
+    // than the minimum give or take rounding
+    fn assert_spaced(level: &Level) {
+        let xs = level.column_xs();
+        for (index, column) in level.columns.iter().enumerate() {
+            assert_close(xs[index] - previous_x(&xs, index), column.spacing);
+            assert_close(column.spacing.max(MIN_LEVEL_SPACING), column.spacing);
+        }
+    }
+
+    #[test]
+    fn inserting_keeps_the_other_columns_in_place() {
+        let mut level = level(&[1000.0, 1000.0]);
+        let xs = level.column_xs();
+
+        assert_eq!(insert_column(&mut level, xs[0] + 500.0, 40.0), Some(1));
+        assert_spaced(&level);
+        let moved = level.column_xs();
+        assert_close(moved[0], xs[0]);
+        assert_close(moved[1], xs[0] + 500.0);
+        assert_close(moved[2], xs[1]);
+        assert_eq!(level.columns[1].gap_y, 40.0);
+
+        assert_eq!(insert_column(&mut level, xs[1] + 600.0, 0.0), Some(3));
+        assert_spaced(&level);
+    }
+
+    #[test]
+    fn inserting_too_close_to_a_column_does_nothing() {
+        let mut level = level(&[1000.0, 1000.0]);
+        let xs = level.column_xs();
+        let columns = level.columns.clone();
+
+        assert_eq!(insert_column(&mut level, xs[0] + 100.0, 0.0), None);
+        assert_eq!(insert_column(&mut level, xs[1] - 100.0, 0.0), None);
+        assert_eq!(insert_column(&mut level, LEVEL_START_X + 100.0, 0.0), None);
+        assert_eq!(level.columns, columns);
+    }
+
+    #[test]
+    fn moving_stays_between_the_neighbours() {
+        let mut level = level(&[1000.0, 1000.0, 1000.0]);
+        let xs = level.column_xs();
+
+        move_column(&mut level, 1, xs[2], MAX_GAP_Y * 2.0);
+        assert_spaced(&level);
+        let moved = level.column_xs();
+        assert_close(moved[0], xs[0]);
+        assert_close(moved[1], xs[2] - MIN_LEVEL_SPACING);
+        assert_close(moved[2], xs[2]);
+        assert_eq!(level.columns[1].gap_y, MAX_GAP_Y);
+
+        move_column(&mut level, 1, xs[0], 0.0);
+        assert_spaced(&level);
+        assert_close(level.column_xs()[1], xs[0] + MIN_LEVEL_SPACING);
+
+        move_column(&mut level, 0, LEVEL_START_X, 0.0);
+        assert_spaced(&level);
+        assert_close(level.column_xs()[0], LEVEL_START_X + MIN_LEVEL_SPACING);
+        assert_close(level.column_xs()[2], xs[2]);
+    }
+
+    #[test]
+    fn removing_keeps_the_other_columns_in_place() {
+        let mut level = level(&[1000.0, 1000.0, 1000.0]);
+        let xs = level.column_xs();
+
+        remove_column(&mut level, 1);
+        assert_spaced(&level);
+        let moved = level.column_xs();
+        assert_close(moved[0], xs[0]);
+        assert_close(moved[1], xs[2]);
+
+        remove_column(&mut level, 0);
+        assert_spaced(&level);
+        assert_close(level.column_xs()[0], xs[2]);
+
+        remove_column(&mut level, 0);
+        assert!(level.columns.is_empty());
+    }
+}
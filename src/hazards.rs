@@ -0,0 +1,333 @@
+use crate::{
+    components::*,
+    modes::{course_rng, pick_course_seed, CourseSeed, GameMode},
+    pipe_behaviors::PipeMotion,
+    reachability::{route_y, PlacedColumn},
+    save::SaveData,
+    settings::{BirdSkin, Settings},
+    setup::bird_atlas,
+    time_scale::{running_set, WorldTime},
+};
+use bevy::{
+    math::{const_vec2, vec3},
+    prelude::*,
+};
+use rand::{prelude::*, rngs::StdRng};
+use std::f32::consts::TAU;
+
+// hazards start showing up at this score
+pub const HAZARDS_FROM_SCORE: usize = 10;
+pub const HAZARD_CHANCE_PER_POINT: f32 = 0.04;
+pub const MAX_HAZARD_CHANCE: f32 = 0.6;
+const HAZARD_SEED_SALT: u64 = 0x4a2a_2d00;
+
+// hitboxes are a bit smaller than the sprites to keep near misses fair
+pub const HAZARD_BIRD: Vec2 = const_vec2!([12.0 * SCALE, 9.0 * SCALE]);
+pub const BALLOON: Vec2 = const_vec2!([26.0, 34.0]);
+// on top of the world speed
+pub const HAZARD_BIRD_SPEED: f32 = 150.0;
+pub const HAZARD_BIRD_BOB: f32 = 20.0;
+pub const HAZARD_BIRD_BOB_SECS: f32 = 0.8;
+pub const BALLOON_RISE_SPEED: f32 = 60.0;
+pub const BALLOON_SWAY: f32 = 12.0;
+pub const BALLOON_SWAY_SECS: f32 = 2.0;
+// room left between a hazard and the route the bird takes past it, for the
+// ups and downs of flapping along it
+pub const ROUTE_CLEARANCE: f32 = PLAYER_HEIGHT / 2.0;
+// heights tried before giving up on a hazard
+const HAZARD_HEIGHT_TRIES: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HazardKind {
+    // flies against the course, bobbing slightly
+    Bird,
+    // drifts with the course while rising
+    Balloon,
+}
+
+impl HazardKind {
+    pub const ALL: [HazardKind; 2] = [HazardKind::Bird, HazardKind::Balloon];
+
+    pub fn hitbox(self) -> Vec2 {
+        match self {
+            HazardKind::Bird => HAZARD_BIRD,
+            HazardKind::Balloon => BALLOON,
+        }
+    }
+}
+
+// A non-pipe obstacle. Its `Blocker` makes `collision_system` treat it like
+// a pipe.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Hazard {
+    pub kind: HazardKind,
+    pub origin_y: f32,
+    pub elapsed: f32,
+    // sideways offset applied so far
+    pub sway: f32,
+}
+
+impl Hazard {
+    // sideways speed relative to the screen
+    fn speed_x(&self) -> f32 {
+        match self.kind {
+            HazardKind::Bird => AUTO_MOVE_SPEED + HAZARD_BIRD_SPEED,
+            HazardKind::Balloon => AUTO_MOVE_SPEED,
+        }
+    }
+
+    pub fn y(&self) -> f32 {
+        match self.kind {
+            HazardKind::Bird => {
+                self.origin_y + HAZARD_BIRD_BOB * (TAU * self.elapsed / HAZARD_BIRD_BOB_SECS).sin()
+            }
+            HazardKind::Balloon => self.origin_y + BALLOON_RISE_SPEED * self.elapsed,
+        }
+    }
+
+    fn sway(&self) -> f32 {
+        match self.kind {
+            HazardKind::Bird => 0.0,
+            HazardKind::Balloon => BALLOON_SWAY * (TAU * self.elapsed / BALLOON_SWAY_SECS).sin(),
+        }
+    }
+
+    // Moves the hazard on by a frame, returns how far it went sideways.
+    pub fn advance(&mut self, delta_seconds: f32) -> f32 {
+        self.elapsed += delta_seconds;
+        let sway = self.sway();
+        let moved = sway - self.sway - self.speed_x() * delta_seconds;
+        self.sway = sway;
+        moved
+    }
+
+    // Whether the hazard, starting out at x, stays clear of the route from
+    // the gap of `previous` to the gap of `next` while it passes the bird.
+    fn clears_route(mut self, mut x: f32, previous: &PlacedColumn, next: &PlacedColumn) -> bool {
+        let hitbox = self.kind.hitbox();
+        let reach_x = (hitbox.x + PLAYER.x) / 2.0;
+        let reach_y = (hitbox.y + PLAYER.y) / 2.0 + ROUTE_CLEARANCE;
+        let mut scrolled = 0.0;
+        while x > PLAYER_POS_X - reach_x {
+            let route = route_y(previous, next, scrolled);
+            if (x - PLAYER_POS_X).abs() < reach_x && (self.y() - route).abs() < reach_y {
+                return false;
+            }
+            x += self.advance(TIME_STEP);
+            scrolled += AUTO_MOVE_SPEED * TIME_STEP;
+        }
+        true
+    }
+}
+
+pub struct HazardAssets {
+    pub birds: Vec<(BirdSkin, Handle<TextureAtlas>)>,
+    pub balloon: Handle<Image>,
+}
+
+pub struct HazardRng(pub StdRng);
+
+impl Default for HazardRng {
+    fn default() -> Self {
+        HazardRng(StdRng::from_entropy())
+    }
+}
+
+pub fn hazard_chance(score: usize) -> f32 {
+    let points = score.saturating_sub(HAZARDS_FROM_SCORE) as f32;
+    (points * HAZARD_CHANCE_PER_POINT).min(MAX_HAZARD_CHANCE)
+}
+
+// Rolls whether a hazard goes halfway between `previous` and the column just
+// placed after it, and at what height. Heights that would block the route
+// between their gaps are rolled again, a few times at most.
+pub fn roll_hazard(
+    rng: &mut impl Rng,
+    score: usize,
+    previous: &PlacedColumn,
+    next: &PlacedColumn,
+    spacing: f32,
+) -> Option<(f32, Hazard)> {
+    if rng.gen::<f32>() >= hazard_chance(score) {
+        return None;
+    }
+
+    let kind = *HazardKind::ALL.choose(rng).unwrap();
+    let x = next.x - spacing / 2.0;
+    (0..HAZARD_HEIGHT_TRIES).find_map(|_| {
+        let origin_y = match kind {
+            HazardKind::Bird => {
+                rng.gen_range(-PIPE_OPENING_Y_POS_FACTOR..PIPE_OPENING_Y_POS_FACTOR)
+            }
+            HazardKind::Balloon => rng.gen_range(FLOOR_POS + FLOOR.y..0.0),
+        };
+        let hazard = Hazard {
+            kind,
+            origin_y,
+            elapsed: 0.0,
+            sway: 0.0,
+        };
+        hazard
+            .clears_route(x, previous, next)
+            .then_some((x, hazard))
+    })
+}
+
+pub fn setup_hazards(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+) {
+    let birds = BirdSkin::ALL
+        .iter()
+        .map(|skin| {
+            let atlas = bird_atlas(asset_server.load(skin.sprite_sheet()));
+            (*skin, texture_atlases.add(atlas))
+        })
+        .collect();
+    commands.insert_resource(HazardAssets {
+        birds,
+        balloon: asset_server.load("sprites/balloon.png"),
+    });
+}
+
+pub fn reset_hazards(
+    mut commands: Commands,
+    seed: Res<CourseSeed>,
+    mut rng: ResMut<HazardRng>,
+    query: Query<Entity, With<Hazard>>,
+) {
+    *rng = HazardRng(course_rng(&seed, HAZARD_SEED_SALT));
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+// Hazards are spawned halfway between a newly placed column and the one
+// before it, out of sight at the right edge.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_hazards(
+    mut commands: Commands,
+    mode: Res<GameMode>,
+    scoreboard: Res<Scoreboard>,
+    settings: Res<Settings>,
+    save_data: Res<SaveData>,
+    assets: Res<HazardAssets>,
+    mut rng: ResMut<HazardRng>,
+    mut column_events: EventReader<ColumnPlacedEvent>,
+    column_query: Query<(&Transform, &PipeMotion), With<AutoMoving>>,
+) {
+    let rng = &mut rng.0;
+    let placed = |(transform, motion): (&Transform, &PipeMotion)| PlacedColumn {
+        x: transform.translation.x,
+        y: transform.translation.y,
+        motion: *motion,
+    };
+    for column in column_events.iter() {
+        // levels only have what they lay out themselves
+        if mode.plays_level() {
+            continue;
+        }
+
+        let next = match column_query.get(column.column) {
+            Ok(next) => placed(next),
+            Err(_) => continue,
+        };
+        let rolled = column_query
+            .iter()
+            .map(placed)
+            .filter(|other| other.x < next.x)
+            .max_by(|a, b| a.x.total_cmp(&b.x))
+            .and_then(|previous| {
+                roll_hazard(rng, scoreboard.score, &previous, &next, column.spacing)
+            });
+        let (x, hazard) = match rolled {
+            Some(rolled) => rolled,
+            None => continue,
+        };
+        let (kind, origin_y) = (hazard.kind, hazard.origin_y);
+
+        match kind {
+            HazardKind::Bird => {
+                // never the same colour as the player
+                let player_skin = save_data.skin_or_default(settings.skin);
+                let others: Vec<_> = assets
+                    .birds
+                    .iter()
+                    .filter(|(skin, _)| *skin != player_skin)
+                    .collect();
+                let atlas = match others.choose(rng) {
+                    Some((_, atlas)) => atlas.clone(),
+                    None => assets.birds[0].1.clone(),
+                };
+                commands
+                    .spawn_bundle(SpriteSheetBundle {
+                        texture_atlas: atlas,
+                        sprite: TextureAtlasSprite {
+                            // facing the player
+                            flip_x: true,
+                            ..default()
+                        },
+                        transform: Transform {
+                            translation: vec3(x, origin_y, 2.0),
+                            scale: BIRD_SIZE,
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(AnimationTimer(Timer::from_seconds(0.1, true)))
+                    .insert(Blocker(kind.hitbox()))
+                    .insert(hazard);
+            }
+            HazardKind::Balloon => {
+                commands
+                    .spawn_bundle(SpriteBundle {
+                        texture: assets.balloon.clone(),
+                        transform: Transform {
+                            translation: vec3(x, origin_y, 2.0),
+                            scale: vec3(2.0, 2.0, 1.0),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .insert(Blocker(kind.hitbox()))
+                    .insert(hazard);
+            }
+        }
+    }
+}
+
+pub fn move_hazards(
+    mut commands: Commands,
+    time: Res<WorldTime>,
+    mut query: Query<(Entity, &mut Hazard, &mut Transform)>,
+) {
+    for (entity, mut hazard, mut transform) in query.iter_mut() {
+        transform.translation.x += hazard.advance(time.delta_seconds());
+        transform.translation.y = hazard.y();
+
+        let gone = transform.translation.x < -SCREEN.x / 2.0 - PIPE.x
+            || transform.translation.y > SCREEN.y / 2.0 + PIPE.x;
+        if gone {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+pub struct HazardsPlugin;
+
+impl Plugin for HazardsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HazardRng>()
+            .add_startup_system(setup_hazards)
+            .add_system_set(
+                SystemSet::on_enter(GameState::Paused)
+                    .with_system(reset_hazards.after(pick_course_seed)),
+            )
+            .add_system_set(
+                running_set()
+                    .with_system(spawn_hazards)
+                    .with_system(move_hazards),
+            );
+    }
+}
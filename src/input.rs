@@ -1,31 +1,80 @@
 pub use crate::components::{AutoMoving, GameState, Player, JUMP_SPEED};
-use crate::components::{GameOverUIInputTimer, ResetGameEvent};
+use crate::components::{FlapEvent, GameOverUIInputTimer, ResetGameEvent};
+use crate::menu::gamepad_just_pressed;
+use crate::settings::Settings;
+use crate::transitions::Transition;
 use bevy::prelude::*;
 
+pub const FLAP_GAMEPAD_BUTTON: GamepadButtonType = GamepadButtonType::South;
+
+pub fn flap_just_pressed(
+    settings: &Settings,
+    keyboard_input: &Input<KeyCode>,
+    gamepads: &Gamepads,
+    gamepad_input: &Input<GamepadButton>,
+) -> bool {
+    keyboard_input.just_pressed(settings.controls.flap_key.key_code())
+        || gamepad_just_pressed(gamepads, gamepad_input, FLAP_GAMEPAD_BUTTON)
+}
+
+pub fn back_just_pressed(
+    keyboard_input: &Input<KeyCode>,
+    gamepads: &Gamepads,
+    gamepad_input: &Input<GamepadButton>,
+) -> bool {
+    keyboard_input.just_pressed(KeyCode::Escape)
+        || gamepad_just_pressed(gamepads, gamepad_input, GamepadButtonType::East)
+}
+
+fn clear_back_input(
+    keyboard_input: &mut Input<KeyCode>,
+    gamepads: &Gamepads,
+    gamepad_input: &mut Input<GamepadButton>,
+) {
+    keyboard_input.reset(KeyCode::Escape);
+    for gamepad in gamepads.iter() {
+        gamepad_input.reset(GamepadButton(*gamepad, GamepadButtonType::East));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn handle_menu_input(
-    mut game_state: ResMut<State<GameState>>,
-    keyboard_input: Res<Input<KeyCode>>,
+    mut transition: ResMut<Transition>,
+    settings: Res<Settings>,
+    mut keyboard_input: ResMut<Input<KeyCode>>,
     mouse_button_input: Res<Input<MouseButton>>,
+    gamepads: Res<Gamepads>,
+    mut gamepad_input: ResMut<Input<GamepadButton>>,
     touches: Res<Touches>,
 ) {
-    if mouse_button_input.just_pressed(MouseButton::Left) {
-        game_state.set(GameState::Running).unwrap();
+    if transition.active() {
+        return;
     }
 
-    if keyboard_input.just_pressed(KeyCode::Space) {
-        game_state.set(GameState::Running).unwrap();
+    if back_just_pressed(&keyboard_input, &gamepads, &gamepad_input) {
+        transition.fade_to(GameState::MainMenu);
+        // the main menu would otherwise read the same press as "back"
+        clear_back_input(&mut keyboard_input, &gamepads, &mut gamepad_input);
+        return;
     }
 
-    for _touch in touches.iter_just_pressed() {
-        game_state.set(GameState::Running).unwrap();
+    let start = mouse_button_input.just_pressed(MouseButton::Left)
+        || flap_just_pressed(&settings, &keyboard_input, &gamepads, &gamepad_input)
+        || touches.iter_just_pressed().next().is_some();
+    if start {
+        transition.fade_to(GameState::Running);
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn handle_game_over_input(
     mut reset_game_event: EventWriter<ResetGameEvent>,
-    mut game_state: ResMut<State<GameState>>,
+    mut transition: ResMut<Transition>,
+    settings: Res<Settings>,
     mut keyboard_input: ResMut<Input<KeyCode>>,
     mut mouse_button_input: ResMut<Input<MouseButton>>,
+    gamepads: Res<Gamepads>,
+    mut gamepad_input: ResMut<Input<GamepadButton>>,
     touches: ResMut<Touches>,
     time: Res<Time>,
     mut query: Query<(Entity, &mut GameOverUIInputTimer)>,
@@ -33,42 +82,57 @@ pub fn handle_game_over_input(
     let (_, mut timer) = query.single_mut();
     timer.0.tick(time.delta());
 
-    if !timer.0.finished() {
+    if !timer.0.finished() || transition.active() {
+        return;
+    }
+
+    if back_just_pressed(&keyboard_input, &gamepads, &gamepad_input) {
+        reset_game_event.send(ResetGameEvent);
+        transition.fade_to(GameState::MainMenu);
+        clear_back_input(&mut keyboard_input, &gamepads, &mut gamepad_input);
         return;
     }
 
     if mouse_button_input.just_pressed(MouseButton::Left)
-        || keyboard_input.just_pressed(KeyCode::Space)
+        || flap_just_pressed(&settings, &keyboard_input, &gamepads, &gamepad_input)
     {
         reset_game_event.send(ResetGameEvent);
-        game_state.set(GameState::Paused).unwrap();
+        transition.fade_to(GameState::Paused);
         mouse_button_input.reset(MouseButton::Left);
-        keyboard_input.reset(KeyCode::Space);
+        keyboard_input.reset(settings.controls.flap_key.key_code());
+        for gamepad in gamepads.iter() {
+            gamepad_input.reset(GamepadButton(*gamepad, FLAP_GAMEPAD_BUTTON));
+        }
     }
 
-    for _touch in touches.iter_just_pressed() {
+    if touches.iter_just_pressed().next().is_some() {
         reset_game_event.send(ResetGameEvent);
-        game_state.set(GameState::Paused).unwrap();
-        break;
+        transition.fade_to(GameState::Paused);
     }
 }
+#[allow(clippy::too_many_arguments)]
 pub fn handle_input_system(
+    settings: Res<Settings>,
     keyboard_input: Res<Input<KeyCode>>,
     mouse_button_input: Res<Input<MouseButton>>,
+    gamepads: Res<Gamepads>,
+    gamepad_input: Res<Input<GamepadButton>>,
     touches: Res<Touches>,
-    mut query: Query<(&mut Player)>,
+    transition: Res<Transition>,
+    mut flap_events: EventWriter<FlapEvent>,
+    mut query: Query<&mut Player>,
 ) {
-    let mut player = query.single_mut();
-
-    if mouse_button_input.just_pressed(MouseButton::Left) {
-        player.movement_speed = JUMP_SPEED;
+    if transition.active() {
+        return;
     }
 
-    if keyboard_input.just_pressed(KeyCode::Space) {
-        player.movement_speed = JUMP_SPEED;
-    }
+    let mut player = query.single_mut();
 
-    for _touch in touches.iter_just_pressed() {
+    let flapped = mouse_button_input.just_pressed(MouseButton::Left)
+        || flap_just_pressed(&settings, &keyboard_input, &gamepads, &gamepad_input)
+        || touches.iter_just_pressed().next().is_some();
+    if flapped {
         player.movement_speed = JUMP_SPEED;
+        flap_events.send(FlapEvent);
     }
 }
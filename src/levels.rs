@@ -0,0 +1,280 @@
+use crate::{
+    coins::bank_coins,
+    components::*,
+    input::handle_game_over_input,
+    locale::{Localization, LocalizedText},
+    modes::{end_run_on_collision, GameMode},
+    pipe_behaviors::{PipeBehavior, PipeMotion},
+    save::record_best_score,
+    setup::game_over_cleanup,
+    time_scale::running_set,
+    transitions::Transition,
+};
+use bevy::{
+    asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_LEVEL: &str = "levels/01.level.ron";
+// Only two column entities are recycled through a level, so each one has
+// to be placed out of sight.
+pub const MIN_LEVEL_SPACING: f32 = 400.0;
+// the first column is spaced from here, so it shows up where random courses
+// start
+pub const LEVEL_START_X: f32 = PIPE_START_X - SPACE_BETWEEN_PIPES;
+// where columns wait once a level has run out of them
+pub const PARKED_COLUMN_X: f32 = 100_000.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LevelColumn {
+    // gap center, 0 is the middle of the screen
+    pub gap_y: f32,
+    pub gap_size: f32,
+    // distance to the column before it
+    pub spacing: f32,
+    pub behavior: PipeBehavior,
+    pub coin: bool,
+    // name of a power-up from `assets/power_ups.ron` to put before the column
+    pub power_up: Option<String>,
+}
+
+impl Default for LevelColumn {
+    fn default() -> Self {
+        LevelColumn {
+            gap_y: 0.0,
+            gap_size: VERTICAL_SPACE_BETWEEN_PIPES,
+            spacing: SPACE_BETWEEN_PIPES,
+            behavior: PipeBehavior::Static,
+            coin: true,
+            power_up: None,
+        }
+    }
+}
+
+// A hand made course, stored as `assets/levels/*.level.ron`. The level is
+// complete once every column has been passed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TypeUuid)]
+#[uuid = "8f1c7c7e-4b3e-4d47-9a52-d1c2a4f0b6e1"]
+#[serde(default)]
+pub struct Level {
+    pub name: String,
+    pub columns: Vec<LevelColumn>,
+}
+
+impl Level {
+    // x of every column at the start of a run
+    pub fn column_xs(&self) -> Vec<f32> {
+        self.columns
+            .iter()
+            .scan(LEVEL_START_X, |x, column| {
+                *x += column.spacing.max(MIN_LEVEL_SPACING);
+                Some(*x)
+            })
+            .collect()
+    }
+}
+
+#[derive(Default)]
+pub struct LevelLoader;
+
+impl AssetLoader for LevelLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let level: Level = ron::de::from_bytes(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(level));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+// The level played in level mode and how far into it the course is.
+pub struct CurrentLevel {
+    pub handle: Handle<Level>,
+    // what the editor is play-testing, from the column the test starts at
+    pub playtest: Handle<Level>,
+    pub next: usize,
+    // set for a run that started before the level was loaded, it keeps to a
+    // random course instead of switching to the level halfway
+    pub random_course: bool,
+}
+
+impl CurrentLevel {
+    pub fn get<'a>(&self, mode: GameMode, levels: &'a Assets<Level>) -> Option<&'a Level> {
+        match mode {
+            GameMode::Playtest => levels.get(&self.playtest),
+            _ => levels.get(&self.handle),
+        }
+    }
+
+    pub fn next_column(&mut self, mode: GameMode, levels: &Assets<Level>) -> Option<LevelColumn> {
+        let column = self.get(mode, levels)?.columns.get(self.next)?.clone();
+        self.next += 1;
+        Some(column)
+    }
+
+    // whether the columns of the current run come from the level
+    pub fn plays(&self, mode: GameMode) -> bool {
+        mode.plays_level() && !self.random_course
+    }
+}
+
+#[derive(Component)]
+pub struct LevelCompleteText;
+
+// Lays out a column from the level. Without one left the column is parked
+// far out of reach.
+pub fn place_level_column(
+    column_events: &mut EventWriter<ColumnPlacedEvent>,
+    entity: Entity,
+    column: Option<LevelColumn>,
+    previous_x: f32,
+    transform: &mut Transform,
+    motion: &mut PipeMotion,
+) {
+    let column = match column {
+        Some(column) => column,
+        None => {
+            transform.translation.x = PARKED_COLUMN_X;
+            motion.place(
+                PipeBehavior::Static,
+                VERTICAL_SPACE_BETWEEN_PIPES,
+                PARKED_COLUMN_X,
+            );
+            column_events.send(ColumnPlacedEvent {
+                column: entity,
+                x: PARKED_COLUMN_X,
+                y: transform.translation.y,
+                spacing: SPACE_BETWEEN_PIPES,
+                coin: false,
+                power_up: None,
+            });
+            return;
+        }
+    };
+
+    let spacing = column.spacing.max(MIN_LEVEL_SPACING);
+    let x = previous_x + spacing;
+    transform.translation.x = x;
+    transform.translation.y = column.gap_y;
+    motion.place(column.behavior, column.gap_size, x);
+    column_events.send(ColumnPlacedEvent {
+        column: entity,
+        x,
+        y: column.gap_y,
+        spacing,
+        coin: column.coin,
+        power_up: column.power_up,
+    });
+}
+
+pub fn setup_level(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    localization: Res<Localization>,
+    mut levels: ResMut<Assets<Level>>,
+) {
+    commands.insert_resource(CurrentLevel {
+        handle: asset_server.load(DEFAULT_LEVEL),
+        playtest: levels.add(Level::default()),
+        next: 0,
+        random_course: false,
+    });
+
+    let style = TextStyle {
+        font: localization.font(&asset_server),
+        font_size: 64.0,
+        color: Color::WHITE,
+    };
+    let alignment = TextAlignment {
+        vertical: VerticalAlign::Center,
+        horizontal: HorizontalAlign::Center,
+    };
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(localization.get("level-complete"), style, alignment),
+            transform: Transform::from_xyz(0.0, 0.0, 11.0),
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(LevelCompleteText)
+        .insert(LocalizedText("level-complete"));
+}
+
+// Runs before `end_run_on_collision`, so passing the last column wins over
+// hitting it on the same frame.
+pub fn check_level_complete(
+    mode: Res<GameMode>,
+    scoreboard: Res<Scoreboard>,
+    levels: Res<Assets<Level>>,
+    current_level: Res<CurrentLevel>,
+    game_state: Res<State<GameState>>,
+    mut transition: ResMut<Transition>,
+) {
+    if !current_level.plays(*mode) || *game_state.current() != GameState::Running {
+        return;
+    }
+
+    let level = match current_level.get(*mode, &levels) {
+        Some(level) => level,
+        None => return,
+    };
+    if scoreboard.score >= level.columns.len() {
+        transition.fade_to(GameState::LevelComplete);
+    }
+}
+
+pub fn show_level_complete(
+    mut text_query: Query<&mut Visibility, With<LevelCompleteText>>,
+    mut timer_query: Query<&mut GameOverUIInputTimer>,
+) {
+    for mut timer in timer_query.iter_mut() {
+        timer.0.reset();
+    }
+    for mut visibility in text_query.iter_mut() {
+        visibility.is_visible = true;
+    }
+}
+
+pub fn hide_level_complete(mut query: Query<&mut Visibility, With<LevelCompleteText>>) {
+    for mut visibility in query.iter_mut() {
+        visibility.is_visible = false;
+    }
+}
+
+pub struct LevelsPlugin;
+
+impl Plugin for LevelsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<Level>()
+            .init_asset_loader::<LevelLoader>()
+            .add_startup_system(setup_level)
+            .add_system_set(
+                running_set().with_system(check_level_complete.before(end_run_on_collision)),
+            )
+            .add_system_set(
+                SystemSet::on_enter(GameState::LevelComplete)
+                    .with_system(show_level_complete)
+                    .with_system(record_best_score)
+                    .with_system(bank_coins),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::LevelComplete).with_system(handle_game_over_input),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::LevelComplete)
+                    .with_system(game_over_cleanup)
+                    .with_system(hide_level_complete),
+            );
+    }
+}
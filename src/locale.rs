@@ -0,0 +1,333 @@
+use crate::settings::Settings;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// drawn with this when a locale does not name a font
+pub const DEFAULT_FONT: &str = "flappy-font.ttf";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+    Russian,
+}
+
+impl Language {
+    pub const ALL: [Language; 3] = [Language::English, Language::German, Language::Russian];
+
+    // Messages from `assets/locales/`.
+    fn source(self) -> &'static str {
+        match self {
+            Language::English => include_str!("../assets/locales/en.ftl"),
+            Language::German => include_str!("../assets/locales/de.ftl"),
+            Language::Russian => include_str!("../assets/locales/ru.ftl"),
+        }
+    }
+}
+
+// The messages of one locale file.
+#[derive(Debug, Clone, Default)]
+pub struct Locale {
+    messages: HashMap<String, String>,
+}
+
+impl Locale {
+    // Reads the `key = value` messages of a Fluent file. Indented lines after
+    // a message continue it on a new line, comments are skipped and anything
+    // else is skipped with a warning.
+    pub fn parse(source: &str) -> Self {
+        let mut messages: HashMap<String, String> = HashMap::new();
+        let mut current: Option<String> = None;
+        for line in source.lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if line.starts_with('#') {
+                current = None;
+                continue;
+            }
+
+            if line.starts_with(char::is_whitespace) {
+                match current.as_ref().and_then(|key| messages.get_mut(key)) {
+                    Some(value) => {
+                        if !value.is_empty() {
+                            value.push('\n');
+                        }
+                        value.push_str(line.trim());
+                    }
+                    None => warn!("locale line outside of a message: {}", line),
+                }
+                continue;
+            }
+
+            current = match line.split_once('=') {
+                Some((key, value)) => {
+                    let key = key.trim().to_string();
+                    messages.insert(key.clone(), value.trim().to_string());
+                    Some(key)
+                }
+                None => {
+                    warn!("locale line is not a message: {}", line);
+                    None
+                }
+            };
+        }
+        Locale { messages }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.messages.get(key).map(String::as_str)
+    }
+}
+
+// Fills in `{ $name }` placeholders, unknown ones are left as they are.
+pub fn format_message(message: &str, args: &[(&str, String)]) -> String {
+    let mut formatted = String::with_capacity(message.len());
+    let mut rest = message;
+    while let Some(start) = rest.find('{') {
+        let end = match rest[start..].find('}') {
+            Some(end) => start + end,
+            None => break,
+        };
+        formatted.push_str(&rest[..start]);
+        let name = rest[start + 1..end].trim().trim_start_matches('$');
+        match args.iter().find(|(arg, _)| *arg == name) {
+            Some((_, value)) => formatted.push_str(value),
+            None => formatted.push_str(&rest[start..=end]),
+        }
+        rest = &rest[end + 1..];
+    }
+    formatted.push_str(rest);
+    formatted
+}
+
+// Every user-visible string goes through here. Messages missing from the
+// selected locale come from English, then from the key itself.
+pub struct Localization {
+    pub language: Language,
+    locales: Vec<Locale>,
+}
+
+impl Localization {
+    pub fn load(language: Language) -> Self {
+        Localization {
+            language,
+            locales: Language::ALL
+                .iter()
+                .map(|language| Locale::parse(language.source()))
+                .collect(),
+        }
+    }
+
+    fn locale(&self, language: Language) -> &Locale {
+        let index = Language::ALL.iter().position(|l| *l == language).unwrap();
+        &self.locales[index]
+    }
+
+    fn message(&self, language: Language, key: &str) -> Option<&str> {
+        self.locale(language)
+            .get(key)
+            .or_else(|| self.locale(Language::English).get(key))
+    }
+
+    pub fn get(&self, key: &str) -> String {
+        self.format(key, &[])
+    }
+
+    pub fn format(&self, key: &str, args: &[(&str, String)]) -> String {
+        match self.message(self.language, key) {
+            Some(message) => format_message(message, args),
+            None => key.to_string(),
+        }
+    }
+
+    // the name a language goes by in its own locale
+    pub fn language_name(&self, language: Language) -> String {
+        self.message(language, "-language")
+            .unwrap_or("?")
+            .to_string()
+    }
+
+    // The font of the selected locale. Locales in scripts flappy-font.ttf
+    // does not cover name one that does.
+    pub fn font(&self, asset_server: &AssetServer) -> Handle<Font> {
+        let path = self
+            .locale(self.language)
+            .get("-font")
+            .unwrap_or(DEFAULT_FONT);
+        asset_server.load(path)
+    }
+}
+
+// Text drawn in the locale's font, switched over when the language changes.
+#[derive(Component)]
+pub struct LocalizedFont;
+
+// Text showing a single message, retranslated when the language changes.
+#[derive(Component)]
+pub struct LocalizedText(pub &'static str);
+
+pub fn sync_language(settings: Res<Settings>, mut localization: ResMut<Localization>) {
+    if settings.is_changed() && localization.language != settings.language {
+        localization.language = settings.language;
+    }
+}
+
+pub fn apply_localization(
+    asset_server: Res<AssetServer>,
+    localization: Res<Localization>,
+    mut font_query: Query<&mut Text, (With<LocalizedFont>, Without<LocalizedText>)>,
+    mut text_query: Query<(&mut Text, &LocalizedText)>,
+) {
+    if !localization.is_changed() || localization.is_added() {
+        return;
+    }
+
+    let font = localization.font(&asset_server);
+    for mut text in font_query.iter_mut() {
+        for section in text.sections.iter_mut() {
+            section.style.font = font.clone();
+        }
+    }
+    for (mut text, LocalizedText(key)) in text_query.iter_mut() {
+        text.sections[0].value = localization.get(key);
+        text.sections[0].style.font = font.clone();
+    }
+}
+
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        let language = app
+            .world
+            .get_resource::<Settings>()
+            .map_or_else(Language::default, |settings| settings.language);
+        app.insert_resource(Localization::load(language))
+            .add_system_to_stage(CoreStage::PreUpdate, sync_language)
+            .add_system(apply_localization);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::achievements::Achievements;
+
+    fn localization(language: Language, sources: [&str; 3]) -> Localization {
+        Localization {
+            language,
+            locales: sources.iter().map(|source| Locale::parse(source)).collect(),
+        }
+    }
+
+    #[test]
+    fn messages_comments_and_continuation_lines() {
+        let source = [
+            "# a comment = not a message",
+            "",
+            "title = FLAPPY BIRD",
+            "-font = fonts/font.ttf",
+            "equation = 1 + 1 = 2",
+            "tutorial =",
+            "    FLAP TO FLY",
+            "    KEEP CLEAR = STAY ALIVE",
+            "",
+            "hint = TAP",
+            "  ANYWHERE",
+            "stray line",
+            "after = STRAY",
+        ];
+        let locale = Locale::parse(&source.join("\n"));
+        assert_eq!(locale.get("title"), Some("FLAPPY BIRD"));
+        assert_eq!(locale.get("-font"), Some("fonts/font.ttf"));
+        assert_eq!(locale.get("equation"), Some("1 + 1 = 2"));
+        assert_eq!(
+            locale.get("tutorial"),
+            Some("FLAP TO FLY\nKEEP CLEAR = STAY ALIVE")
+        );
+        assert_eq!(locale.get("hint"), Some("TAP\nANYWHERE"));
+        assert_eq!(locale.get("after"), Some("STRAY"));
+        assert_eq!(locale.get("# a comment"), None);
+        assert_eq!(locale.get("stray line"), None);
+        assert_eq!(locale.messages.len(), 6);
+    }
+
+    #[test]
+    fn placeholders_are_filled_in() {
+        let args = [("score", "12".to_string()), ("best", "40".to_string())];
+        assert_eq!(
+            format_message("SCORE { $score } BEST {$best}", &args),
+            "SCORE 12 BEST 40"
+        );
+        assert_eq!(format_message("{ $score }{ $score }", &args), "1212");
+        assert_eq!(format_message("NO PLACEHOLDERS", &args), "NO PLACEHOLDERS");
+        // unknown and unclosed placeholders are left as they are
+        assert_eq!(
+            format_message("{ $coins } COINS", &args),
+            "{ $coins } COINS"
+        );
+        assert_eq!(format_message("SCORE { $score", &args), "SCORE { $score");
+        assert_eq!(
+            format_message("{ $score } OF { $best", &args),
+            "12 OF { $best"
+        );
+    }
+
+    #[test]
+    fn missing_messages_fall_back_to_english_then_the_key() {
+        let sources = [
+            "title = FLAPPY BIRD\nscore = SCORE { $score }",
+            "title = FLATTERVOGEL",
+            "",
+        ];
+        let german = localization(Language::German, sources);
+        assert_eq!(german.get("title"), "FLATTERVOGEL");
+        assert_eq!(
+            german.format("score", &[("score", "3".to_string())]),
+            "SCORE 3"
+        );
+        assert_eq!(german.get("missing"), "missing");
+
+        let russian = localization(Language::Russian, sources);
+        assert_eq!(russian.get("title"), "FLAPPY BIRD");
+        assert_eq!(russian.get("missing"), "missing");
+    }
+
+    // Every message key spelled out in the code, the hyphenated string
+    // literals that aren't placeholder names, has an English message.
+    #[test]
+    fn every_key_in_the_code_is_in_english() {
+        let english = Locale::parse(Language::English.source());
+        let source_dir = concat!(env!("CARGO_MANIFEST_DIR"), "/src");
+        let mut missing = Vec::new();
+        for entry in std::fs::read_dir(source_dir).unwrap() {
+            let source = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+            let keys = source.split('"').skip(1).step_by(2).filter(|literal| {
+                let name = literal.trim_start_matches('-');
+                name.contains('-')
+                    && name.starts_with(|c: char| c.is_ascii_lowercase())
+                    && name
+                        .chars()
+                        .all(|c| c == '-' || c.is_ascii_lowercase() || c.is_ascii_digit())
+            });
+            for key in keys {
+                let placeholder = format!("${}", key);
+                if english.get(key).is_none() && !Language::English.source().contains(&placeholder)
+                {
+                    missing.push(key.to_string());
+                }
+            }
+        }
+        for achievement in Achievements::load().0 {
+            for key in [achievement.name_key(), achievement.description_key()] {
+                if english.get(&key).is_none() {
+                    missing.push(key);
+                }
+            }
+        }
+        assert!(missing.is_empty(), "missing from en.ftl: {:?}", missing);
+    }
+}
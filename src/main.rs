@@ -1,29 +1,79 @@
-use bevy::{math::vec3, prelude::*, sprite::collide_aabb::collide, window::PresentMode};
-use rand::prelude::*;
+use bevy::{math::vec3, prelude::*, sprite::collide_aabb::collide};
+pub mod accessibility;
+use accessibility::AccessibilityPlugin;
+pub mod achievements;
+use achievements::AchievementsPlugin;
+pub mod camera_effects;
+use camera_effects::CameraEffectsPlugin;
+pub mod coins;
+use coins::CoinsPlugin;
+pub mod death;
+use death::{land_system, nose_dive_system, start_dying};
+pub mod debug;
+use debug::DebugPlugin;
+#[cfg(feature = "dev")]
+pub mod dev_time;
+pub mod editor;
+use editor::EditorPlugin;
+pub mod levels;
+use levels::{place_level_column, CurrentLevel, Level, LevelsPlugin, LEVEL_START_X};
+pub mod locale;
+use locale::LocalePlugin;
+pub mod main_menu;
+use main_menu::MainMenuPlugin;
+pub mod hazards;
+use hazards::{Hazard, HazardsPlugin};
 pub mod input;
 use input::{handle_game_over_input, handle_input_system, handle_menu_input};
 pub mod components;
 use components::*;
 pub mod setup;
 use setup::*;
+pub mod menu;
+use menu::MenuPlugin;
+pub mod modes;
+use modes::{pick_course_seed, CourseRng, GameMode, ModesPlugin};
+pub mod parallax;
+use parallax::ParallaxPlugin;
+pub mod particles;
+use particles::ParticlesPlugin;
+pub mod pipe_behaviors;
+use pipe_behaviors::{animate_pipes, PipeMotion};
+pub mod power_ups;
+use power_ups::{ActivePowerUps, PowerUpsPlugin, SHIELD_BREAK_INVULNERABILITY_SECS};
+pub mod practice;
+use practice::PracticePlugin;
+pub mod reachability;
+use reachability::{fall_speed, random_gap_y, PlacedColumn};
+pub mod save;
+use save::{record_best_score, SaveData};
+pub mod settings;
+use settings::{Settings, SettingsPlugin};
+pub mod settings_menu;
+use settings_menu::SettingsMenuPlugin;
+pub mod stats;
+use stats::StatsPlugin;
+pub mod sounds;
+use sounds::SoundsPlugin;
+pub mod storage;
+pub mod telemetry;
+use telemetry::TelemetryPlugin;
+pub mod time_attack;
+use time_attack::TimeAttackPlugin;
+pub mod time_scale;
+use time_scale::{running_set, TimeScalePlugin, WorldTime};
+pub mod transitions;
+use transitions::TransitionsPlugin;
 
 fn player_movement_system(
-    time: Res<Time>,
+    time: Res<WorldTime>,
     gravity: Res<Gravity>,
-    texture_atlases: Res<Assets<TextureAtlas>>,
-    mut query: Query<(
-        &mut Player,
-        &mut Transform,
-        &TextureAtlasSprite,
-        &Handle<TextureAtlas>,
-    )>,
+    settings: Res<Settings>,
+    mut query: Query<(&mut Player, &mut Transform)>,
 ) {
-    let (mut player, mut transform, sprite, texture_atlas_handle) = query.single_mut();
-    let texture_atlas = texture_atlases.get(texture_atlas_handle).unwrap();
-    let texture_height = texture_atlas.size.y;
+    let (mut player, mut transform) = query.single_mut();
 
-    let new_speed = player.movement_speed + gravity.0;
-    player.movement_speed = new_speed.clamp(-1000.0, 10000.0);
+    player.movement_speed = fall_speed(player.movement_speed, gravity.0, time.delta_seconds());
 
     let new_player_pos = transform.translation.y
         + vec3(
@@ -32,11 +82,13 @@ fn player_movement_system(
             2.0,
         );
     transform.translation = new_player_pos.clamp(
-        vec3(PLAYER_POS_X, FLOOR_POS + 90.0, 2.0),
+        vec3(PLAYER_POS_X, PLAYER_MIN_Y, 2.0),
         vec3(PLAYER_POS_X, SCREEN.y / 2.0, 2.0),
     );
 
-    if player.movement_speed > 0.0 {
+    if settings.accessibility.reduced_motion {
+        player.angle = 0.0;
+    } else if player.movement_speed > 0.0 {
         player.angle = 30.0;
     } else {
         player.angle = (player.angle - 180.0 * time.delta_seconds()).clamp(-90.0, 45.0);
@@ -46,7 +98,7 @@ fn player_movement_system(
 }
 
 fn animate_sprite_system(
-    time: Res<Time>,
+    time: Res<WorldTime>,
     texture_atlases: Res<Assets<TextureAtlas>>,
     mut query: Query<(
         &mut AnimationTimer,
@@ -63,45 +115,107 @@ fn animate_sprite_system(
     }
 }
 
-fn auto_move_system(mut query: Query<(&AutoMoving, &mut Transform, Option<&mut Countable>)>) {
-    let mut rng = thread_rng();
+#[allow(clippy::too_many_arguments)]
+fn auto_move_system(
+    time: Res<WorldTime>,
+    mut column_events: EventWriter<ColumnPlacedEvent>,
+    scoreboard: Res<Scoreboard>,
+    mode: Res<GameMode>,
+    levels: Res<Assets<Level>>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut course_rng: ResMut<CourseRng>,
+    mut query: Query<(
+        Entity,
+        &AutoMoving,
+        &mut Transform,
+        &mut Countable,
+        &mut PipeMotion,
+    )>,
+) {
+    let rng = &mut course_rng.0;
+    let step = AUTO_MOVE_SPEED * time.delta_seconds();
+    // new columns are spaced from the one placed last, the rightmost
+    let mut last = query
+        .iter()
+        .map(|(_, _, transform, _, motion)| PlacedColumn {
+            x: transform.translation.x - step,
+            y: transform.translation.y,
+            motion: *motion,
+        })
+        .max_by(|a, b| a.x.total_cmp(&b.x));
 
-    for (auto_moving, mut transform, countable) in query.iter_mut() {
-        transform.translation.x -= AUTO_MOVE_SPEED;
+    for (entity, auto_moving, mut transform, mut countable, mut motion) in query.iter_mut() {
+        transform.translation.x -= step;
 
         // if out of screen -> move to other side
         if transform.translation.x + auto_moving.width / 2.0 < -SCREEN.x / 2.0 {
-            if let Some(mut countable) = countable {
-                countable.0 = true;
+            countable.0 = true;
+
+            if current_level.plays(*mode) {
+                let column = current_level.next_column(*mode, &levels);
+                let last_x = last.map_or(LEVEL_START_X, |last| last.x);
+                place_level_column(
+                    &mut column_events,
+                    entity,
+                    column,
+                    last_x,
+                    &mut transform,
+                    &mut motion,
+                );
+            } else {
+                transform.translation.x =
+                    SCREEN.x / 2.0 + auto_moving.width / 2.0 + auto_moving.displacement;
+                transform.translation.y = random_gap_y(
+                    rng,
+                    auto_moving,
+                    scoreboard.score,
+                    transform.translation.x,
+                    last.as_ref(),
+                    &mut motion,
+                );
+                column_events.send(ColumnPlacedEvent {
+                    column: entity,
+                    x: transform.translation.x,
+                    y: transform.translation.y,
+                    spacing: SPACE_BETWEEN_PIPES,
+                    coin: true,
+                    power_up: None,
+                });
+            }
+
+            let placed = PlacedColumn {
+                x: transform.translation.x,
+                y: transform.translation.y,
+                motion: *motion,
+            };
+            if last.map_or(true, |last| placed.x > last.x) {
+                last = Some(placed);
             }
-            transform.translation.x =
-                SCREEN.x / 2.0 + auto_moving.width / 2.0 + auto_moving.displacement;
-            transform.translation.y =
-                auto_moving.initial.y + auto_moving.randomness.y * rng.gen_range(-1.0..1.0);
         }
     }
 }
 
-fn animate_world(mut query: Query<(&SpeedAnimated, &mut Transform)>) {
+fn animate_world(time: Res<WorldTime>, mut query: Query<(&SpeedAnimated, &mut Transform)>) {
     let iter = query.iter_mut();
     let total: f32 = iter.len() as f32;
     for (speed_animated, mut transform) in iter {
-        transform.translation.x -= AUTO_MOVE_SPEED;
+        transform.translation.x -= AUTO_MOVE_SPEED * time.delta_seconds();
 
         if transform.translation.x + speed_animated.width / 2.0 < -SCREEN.x / 2.0 {
-            transform.translation.x = transform.translation.x + (speed_animated.width * total);
+            transform.translation.x += speed_animated.width * total;
         }
     }
 }
 
 fn point_count_system(
     mut scoreboard: ResMut<Scoreboard>,
-    mut countable_query: Query<(&mut Countable, &Transform)>,
+    mut scored_events: EventWriter<PointScoredEvent>,
+    mut countable_query: Query<(Entity, &mut Countable, &Transform)>,
     player_query: Query<(&Player, &Transform)>,
 ) {
     let (_, player_transform) = player_query.single();
 
-    for (mut countable, transform) in countable_query.iter_mut() {
+    for (entity, mut countable, transform) in countable_query.iter_mut() {
         if !countable.0 {
             continue;
         }
@@ -109,6 +223,7 @@ fn point_count_system(
         if transform.translation.x < player_transform.translation.x {
             countable.0 = false;
             scoreboard.score += 1;
+            scored_events.send(PointScoredEvent { column: entity });
         }
     }
 }
@@ -120,31 +235,43 @@ fn update_score_text(scoreboard: Res<Scoreboard>, mut query: Query<(&ScoreText,
 }
 
 fn collision_system(
-    mut game_state: ResMut<State<GameState>>,
-    collider_query: Query<(&Collider, &GlobalTransform)>,
-    blocker_query: Query<(&Blocker, &GlobalTransform)>,
+    mut commands: Commands,
+    mut collision_events: EventWriter<CollisionEvent>,
+    mut power_ups: ResMut<ActivePowerUps>,
+    collider_query: Query<(Entity, &Collider, &GlobalTransform), Without<Invulnerable>>,
+    blocker_query: Query<(&Blocker, &GlobalTransform, Option<&Floor>, Option<&Hazard>)>,
 ) {
-    for (_, c_transf) in collider_query.iter() {
-        for (blocker, b_transf) in blocker_query.iter() {
+    let hitbox = power_ups.player_hitbox();
+    for (entity, _, c_transf) in collider_query.iter() {
+        for (blocker, b_transf, floor, hazard) in blocker_query.iter() {
             let collision = collide(
                 c_transf.translation,
-                PLAYER,
+                hitbox,
                 b_transf.translation,
                 blocker.0,
             );
             match collision {
                 Some(_collision) => {
-                    println!(
-                        "c_pos: {}, c_size: {}",
-                        c_transf.translation.to_string(),
-                        PLAYER
-                    );
-                    println!(
-                        "b_pos: {}, b_size: {}",
-                        b_transf.translation.to_string(),
-                        PIPE
+                    debug!(
+                        "collision: collider at {} ({}), blocker at {} ({})",
+                        c_transf.translation, hitbox, b_transf.translation, blocker.0
                     );
-                    game_state.set(GameState::GameOver).unwrap();
+                    if power_ups.consume_shield() {
+                        // a broken shield buys a moment to get clear
+                        commands
+                            .entity(entity)
+                            .insert(Invulnerable(Timer::from_seconds(
+                                SHIELD_BREAK_INVULNERABILITY_SECS,
+                                false,
+                            )));
+                    } else {
+                        let obstacle = match (floor, hazard) {
+                            (Some(_), _) => Obstacle::Floor,
+                            (_, Some(_)) => Obstacle::Hazard,
+                            _ => Obstacle::Pipe,
+                        };
+                        collision_events.send(CollisionEvent { obstacle });
+                    }
                     break;
                 }
                 None => {
@@ -155,44 +282,113 @@ fn collision_system(
     }
 }
 
+fn invulnerability_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Invulnerable, &mut Visibility)>,
+) {
+    for (entity, mut invulnerable, mut visibility) in query.iter_mut() {
+        invulnerable.0.tick(time.delta());
+        if invulnerable.0.finished() {
+            visibility.is_visible = true;
+            commands.entity(entity).remove::<Invulnerable>();
+        } else {
+            // blink five times a second
+            visibility.is_visible = (invulnerable.0.elapsed_secs() * 10.0) as u32 % 2 == 0;
+        }
+    }
+}
+
 fn main() {
+    let settings = Settings::load();
+
     App::new()
         .insert_resource(WindowDescriptor {
             title: "Flappy bird in Rust".to_string(),
             width: SCREEN.x,
             height: SCREEN.y,
-            present_mode: PresentMode::Fifo,
+            mode: settings.display.mode.window_mode(),
+            present_mode: settings.display.present_mode(),
             ..default()
         })
+        .insert_resource(settings)
         .add_plugins(DefaultPlugins)
+        .add_plugin(SettingsPlugin)
+        .add_plugin(LocalePlugin)
+        .add_plugin(AccessibilityPlugin)
+        .add_plugin(MenuPlugin)
+        .add_plugin(SettingsMenuPlugin)
+        .add_plugin(SoundsPlugin)
+        .add_plugin(MainMenuPlugin)
+        .add_plugin(StatsPlugin)
+        .add_plugin(AchievementsPlugin)
+        .add_plugin(ModesPlugin)
+        .add_plugin(TimeAttackPlugin)
+        .add_plugin(PracticePlugin)
+        .add_plugin(TimeScalePlugin)
+        .add_plugin(CoinsPlugin)
+        .add_plugin(PowerUpsPlugin)
+        .add_plugin(HazardsPlugin)
+        .add_plugin(LevelsPlugin)
+        .add_plugin(EditorPlugin)
+        .add_plugin(ParallaxPlugin)
+        .add_plugin(ParticlesPlugin)
+        .add_plugin(CameraEffectsPlugin)
+        .add_plugin(TransitionsPlugin)
+        .add_plugin(DebugPlugin)
+        .add_plugin(TelemetryPlugin)
         .insert_resource(Scoreboard { score: 0 })
         .insert_resource(Gravity(SCALED_GRAVITY))
-        .add_state(GameState::Paused)
+        .insert_resource(SaveData::load())
+        .add_state(GameState::MainMenu)
         .add_startup_system(setup_player)
         .add_startup_system(setup_floor)
         .add_startup_system(setup_pipes)
         .add_startup_system(setup_font)
         .add_startup_system(setup_game_over_ui)
+        .add_startup_system(setup_ready_ui)
         .add_event::<ResetGameEvent>()
+        .add_event::<CollisionEvent>()
+        .add_event::<ColumnPlacedEvent>()
+        .add_event::<FlapEvent>()
+        .add_event::<PointScoredEvent>()
+        .add_system_set(
+            SystemSet::on_enter(GameState::Paused)
+                .with_system(reset_course.after(pick_course_seed))
+                .with_system(set_ready_ui_visible),
+        )
+        .add_system_set(SystemSet::on_exit(GameState::Paused).with_system(set_ready_ui_hidden))
         .add_system_set(
             SystemSet::on_update(GameState::Paused)
                 .with_system(handle_menu_input)
+                .with_system(animate_pipes)
                 .with_system(animate_world)
                 .with_system(animate_sprite_system),
         )
         .add_system_set(
-            SystemSet::on_update(GameState::Running)
+            running_set()
                 .with_system(auto_move_system)
+                .with_system(animate_pipes.after(auto_move_system))
                 .with_system(animate_world)
                 .with_system(collision_system)
+                .with_system(invulnerability_system)
                 .with_system(point_count_system)
                 .with_system(update_score_text)
                 .with_system(handle_input_system)
                 .with_system(animate_sprite_system)
                 .with_system(player_movement_system),
         )
+        .add_system_set(SystemSet::on_enter(GameState::Dying).with_system(start_dying))
+        .add_system_set(
+            SystemSet::on_update(GameState::Dying)
+                .with_system(nose_dive_system)
+                .with_system(player_movement_system.after(nose_dive_system))
+                .with_system(land_system.after(player_movement_system)),
+        )
         .add_system_set(
-            SystemSet::on_enter(GameState::GameOver).with_system(set_game_over_ui_visible),
+            SystemSet::on_enter(GameState::GameOver)
+                .with_system(set_game_over_ui_visible)
+                .with_system(record_best_score),
         )
         .add_system_set(
             SystemSet::on_update(GameState::GameOver)
@@ -0,0 +1,101 @@
+use crate::{
+    components::GameState, locale::Localization, menu::*, modes::GameMode, transitions::Transition,
+};
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct MainMenuScreen;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MainMenuItem {
+    Play(GameMode),
+    Editor,
+    Stats,
+    Achievements,
+    Settings,
+}
+
+fn main_menu_items() -> Vec<MainMenuItem> {
+    GameMode::ALL
+        .iter()
+        .map(|mode| MainMenuItem::Play(*mode))
+        .chain([
+            MainMenuItem::Editor,
+            MainMenuItem::Stats,
+            MainMenuItem::Achievements,
+            MainMenuItem::Settings,
+        ])
+        .collect()
+}
+
+impl MainMenuItem {
+    fn key(self) -> &'static str {
+        match self {
+            MainMenuItem::Play(mode) => mode.key(),
+            MainMenuItem::Editor => "menu-editor",
+            MainMenuItem::Stats => "menu-stats",
+            MainMenuItem::Achievements => "menu-achievements",
+            MainMenuItem::Settings => "menu-settings",
+        }
+    }
+}
+
+pub fn setup_main_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    localization: Res<Localization>,
+) {
+    let font = localization.font(&asset_server);
+    commands
+        .spawn_bundle(menu_root())
+        .insert(MainMenuScreen)
+        .with_children(|parent| {
+            spawn_menu_title(parent, font.clone(), &localization.get("title"));
+            for (index, item) in main_menu_items().iter().enumerate() {
+                spawn_menu_item(parent, font.clone(), index, &localization.get(item.key()));
+            }
+        });
+}
+
+pub fn handle_main_menu(
+    mut game_state: ResMut<State<GameState>>,
+    mut transition: ResMut<Transition>,
+    mut mode: ResMut<GameMode>,
+    mut menu_events: EventReader<MenuEvent>,
+) {
+    if transition.active() {
+        return;
+    }
+
+    let items = main_menu_items();
+    for event in menu_events.iter() {
+        if event.action != MenuAction::Activate {
+            continue;
+        }
+
+        match items[event.item.min(items.len() - 1)] {
+            MainMenuItem::Play(selected) => {
+                *mode = selected;
+                transition.fade_to(GameState::Paused);
+            }
+            MainMenuItem::Editor => game_state.set(GameState::Editor).unwrap(),
+            MainMenuItem::Stats => game_state.set(GameState::Stats).unwrap(),
+            MainMenuItem::Achievements => game_state.set(GameState::Achievements).unwrap(),
+            MainMenuItem::Settings => game_state.set(GameState::Settings).unwrap(),
+        }
+        return;
+    }
+}
+
+pub struct MainMenuPlugin;
+
+impl Plugin for MainMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_enter(GameState::MainMenu).with_system(setup_main_menu))
+            .add_system_set(SystemSet::on_update(GameState::MainMenu).with_system(handle_main_menu))
+            .add_system_set(
+                SystemSet::on_exit(GameState::MainMenu)
+                    .with_system(despawn_screen::<MainMenuScreen>),
+            );
+    }
+}
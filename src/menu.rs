@@ -0,0 +1,285 @@
+use crate::{
+    accessibility::{ScaledNode, ScaledText},
+    components::GameState,
+    locale::LocalizedFont,
+};
+use bevy::{ecs::system::EntityCommands, math::const_vec2, prelude::*};
+
+pub const MENU_FONT_SIZE: f32 = 36.0;
+pub const MENU_TITLE_FONT_SIZE: f32 = 64.0;
+pub const MENU_ITEM_SIZE: Vec2 = const_vec2!([440.0, 52.0]);
+pub const MENU_ITEM_MARGIN: Val = Val::Px(4.0);
+
+const ITEM_COLOR: Color = Color::rgba(0.0, 0.0, 0.0, 0.45);
+const FOCUSED_ITEM_COLOR: Color = Color::rgba(0.87, 0.45, 0.11, 0.9);
+const ITEM_TEXT_COLOR: Color = Color::WHITE;
+
+// One selectable row of a menu screen, identified by its position.
+#[derive(Component)]
+pub struct MenuItem(pub usize);
+
+#[derive(Component)]
+pub struct MenuItemText;
+
+// Index of the menu row that keyboard and gamepad input acts on.
+#[derive(Default)]
+pub struct MenuFocus(pub usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuAction {
+    Previous,
+    Next,
+    Activate,
+    Back,
+}
+
+// Sent for every menu interaction regardless of the device it came from.
+// Screens decide what an action means for the focused row.
+pub struct MenuEvent {
+    pub item: usize,
+    pub action: MenuAction,
+}
+
+pub fn gamepad_just_pressed(
+    gamepads: &Gamepads,
+    gamepad_input: &Input<GamepadButton>,
+    button: GamepadButtonType,
+) -> bool {
+    gamepads
+        .iter()
+        .any(|gamepad| gamepad_input.just_pressed(GamepadButton(*gamepad, button)))
+}
+
+pub fn menu_navigation_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_input: Res<Input<GamepadButton>>,
+    mut focus: ResMut<MenuFocus>,
+    mut menu_events: EventWriter<MenuEvent>,
+    item_query: Query<(&MenuItem, &Interaction), Changed<Interaction>>,
+    all_items: Query<&MenuItem>,
+) {
+    let count = all_items.iter().count();
+    if count == 0 {
+        return;
+    }
+
+    // mouse and touch
+    for (item, interaction) in item_query.iter() {
+        match interaction {
+            Interaction::Clicked => {
+                focus.0 = item.0;
+                menu_events.send(MenuEvent {
+                    item: item.0,
+                    action: MenuAction::Activate,
+                });
+            }
+            Interaction::Hovered => focus.0 = item.0,
+            Interaction::None => {}
+        }
+    }
+
+    let pressed = |key: KeyCode, button: GamepadButtonType| {
+        keyboard_input.just_pressed(key) || gamepad_just_pressed(&gamepads, &gamepad_input, button)
+    };
+
+    if pressed(KeyCode::Up, GamepadButtonType::DPadUp) {
+        focus.0 = (focus.0 + count - 1) % count;
+    }
+    if pressed(KeyCode::Down, GamepadButtonType::DPadDown) {
+        focus.0 = (focus.0 + 1) % count;
+    }
+    focus.0 = focus.0.min(count - 1);
+
+    let mut send = |action| {
+        menu_events.send(MenuEvent {
+            item: focus.0,
+            action,
+        })
+    };
+    if pressed(KeyCode::Left, GamepadButtonType::DPadLeft) {
+        send(MenuAction::Previous);
+    }
+    if pressed(KeyCode::Right, GamepadButtonType::DPadRight) {
+        send(MenuAction::Next);
+    }
+    if pressed(KeyCode::Return, GamepadButtonType::South) {
+        send(MenuAction::Activate);
+    }
+    if pressed(KeyCode::Escape, GamepadButtonType::East) {
+        send(MenuAction::Back);
+    }
+}
+
+pub fn highlight_focused_item(focus: Res<MenuFocus>, mut query: Query<(&MenuItem, &mut UiColor)>) {
+    for (item, mut color) in query.iter_mut() {
+        let wanted = if item.0 == focus.0 {
+            FOCUSED_ITEM_COLOR
+        } else {
+            ITEM_COLOR
+        };
+        if color.0 != wanted {
+            color.0 = wanted;
+        }
+    }
+}
+
+// Run when leaving a menu screen so the press that closed it is not seen
+// again by the screen that opens in the same frame.
+pub fn clear_menu_input(
+    mut keyboard_input: ResMut<Input<KeyCode>>,
+    mut mouse_button_input: ResMut<Input<MouseButton>>,
+    gamepads: Res<Gamepads>,
+    mut gamepad_input: ResMut<Input<GamepadButton>>,
+) {
+    keyboard_input.reset(KeyCode::Escape);
+    keyboard_input.reset(KeyCode::Return);
+    mouse_button_input.reset(MouseButton::Left);
+    for gamepad in gamepads.iter() {
+        gamepad_input.reset(GamepadButton(*gamepad, GamepadButtonType::South));
+        gamepad_input.reset(GamepadButton(*gamepad, GamepadButtonType::East));
+    }
+}
+
+pub fn reset_menu_focus(mut focus: ResMut<MenuFocus>) {
+    focus.0 = 0;
+}
+
+pub fn menu_root() -> NodeBundle {
+    NodeBundle {
+        style: Style {
+            size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+            // ui coordinates grow upwards, reverse to list items top to bottom
+            flex_direction: FlexDirection::ColumnReverse,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        color: UiColor(Color::rgba(0.0, 0.0, 0.0, 0.35)),
+        ..default()
+    }
+}
+
+pub fn spawn_menu_title<'w, 's, 'a>(
+    parent: &'a mut ChildBuilder<'w, 's, '_>,
+    font: Handle<Font>,
+    title: &str,
+) -> EntityCommands<'w, 's, 'a> {
+    let mut entity = parent.spawn_bundle(TextBundle {
+        style: Style {
+            margin: Rect {
+                bottom: Val::Px(24.0),
+                ..default()
+            },
+            ..default()
+        },
+        text: Text::with_section(
+            title,
+            TextStyle {
+                font,
+                font_size: MENU_TITLE_FONT_SIZE,
+                color: ITEM_TEXT_COLOR,
+            },
+            default(),
+        ),
+        ..default()
+    });
+    entity
+        .insert(LocalizedFont)
+        .insert(ScaledText(MENU_TITLE_FONT_SIZE));
+    entity
+}
+
+pub fn spawn_menu_text<'w, 's, 'a>(
+    parent: &'a mut ChildBuilder<'w, 's, '_>,
+    font: Handle<Font>,
+    value: &str,
+) -> EntityCommands<'w, 's, 'a> {
+    let mut entity = parent.spawn_bundle(TextBundle {
+        style: Style {
+            margin: Rect::all(MENU_ITEM_MARGIN),
+            ..default()
+        },
+        text: Text::with_section(
+            value,
+            TextStyle {
+                font,
+                font_size: MENU_FONT_SIZE,
+                color: ITEM_TEXT_COLOR,
+            },
+            default(),
+        ),
+        ..default()
+    });
+    entity
+        .insert(LocalizedFont)
+        .insert(ScaledText(MENU_FONT_SIZE));
+    entity
+}
+
+pub fn spawn_menu_item(parent: &mut ChildBuilder, font: Handle<Font>, index: usize, label: &str) {
+    parent
+        .spawn_bundle(ButtonBundle {
+            style: Style {
+                size: Size::new(Val::Px(MENU_ITEM_SIZE.x), Val::Px(MENU_ITEM_SIZE.y)),
+                margin: Rect::all(MENU_ITEM_MARGIN),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            color: UiColor(ITEM_COLOR),
+            ..default()
+        })
+        .insert(MenuItem(index))
+        .insert(ScaledNode(MENU_ITEM_SIZE))
+        .with_children(|button| {
+            button
+                .spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        label,
+                        TextStyle {
+                            font,
+                            font_size: MENU_FONT_SIZE,
+                            color: ITEM_TEXT_COLOR,
+                        },
+                        default(),
+                    ),
+                    ..default()
+                })
+                .insert(MenuItemText)
+                .insert(LocalizedFont)
+                .insert(ScaledText(MENU_FONT_SIZE));
+        });
+}
+
+pub fn despawn_screen<T: Component>(mut commands: Commands, query: Query<Entity, With<T>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+// States that show a navigable menu screen.
+pub const MENU_STATES: [GameState; 4] = [
+    GameState::MainMenu,
+    GameState::Settings,
+    GameState::Stats,
+    GameState::Achievements,
+];
+
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MenuFocus>().add_event::<MenuEvent>();
+
+        for state in MENU_STATES {
+            app.add_system_set(SystemSet::on_enter(state).with_system(reset_menu_focus))
+                .add_system_set(
+                    SystemSet::on_update(state)
+                        .with_system(menu_navigation_system)
+                        .with_system(highlight_focused_item),
+                )
+                .add_system_set(SystemSet::on_exit(state).with_system(clear_menu_input));
+        }
+    }
+}
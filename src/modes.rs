@@ -0,0 +1,191 @@
+use crate::{components::*, time_scale::running_set, transitions::Transition};
+use bevy::{math::vec3, prelude::*};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+const SECONDS_PER_DAY: u64 = 60 * 60 * 24;
+pub const RESPAWN_INVULNERABILITY_SECS: f32 = 1.5;
+
+// The mode picked in the main menu. Modes share the player, pipe and floor
+// setup and plug their own rules in as systems that check the current mode.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GameMode {
+    #[default]
+    Classic,
+    TimeAttack,
+    Practice,
+    DailyChallenge,
+    Level,
+    // a level being tried out from the editor, not offered in the menu
+    Playtest,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 5] = [
+        GameMode::Classic,
+        GameMode::TimeAttack,
+        GameMode::Practice,
+        GameMode::DailyChallenge,
+        GameMode::Level,
+    ];
+
+    // localization key of the mode's name
+    pub fn key(self) -> &'static str {
+        match self {
+            GameMode::Classic => "mode-classic",
+            GameMode::TimeAttack => "mode-time-attack",
+            GameMode::Practice => "mode-practice",
+            GameMode::DailyChallenge => "mode-daily-challenge",
+            GameMode::Level => "mode-level",
+            GameMode::Playtest => "mode-playtest",
+        }
+    }
+
+    // whether hitting a blocker ends the run
+    pub fn ends_on_collision(self) -> bool {
+        match self {
+            GameMode::Classic | GameMode::DailyChallenge | GameMode::Level | GameMode::Playtest => {
+                true
+            }
+            GameMode::TimeAttack | GameMode::Practice => false,
+        }
+    }
+
+    pub fn records_high_score(self) -> bool {
+        match self {
+            GameMode::Classic
+            | GameMode::TimeAttack
+            | GameMode::DailyChallenge
+            | GameMode::Level => true,
+            GameMode::Practice | GameMode::Playtest => false,
+        }
+    }
+
+    // whether the course comes from a level instead of being random
+    pub fn plays_level(self) -> bool {
+        matches!(self, GameMode::Level | GameMode::Playtest)
+    }
+
+    // fixed course seed, None for a fresh random course every run
+    pub fn course_seed(self) -> Option<u64> {
+        match self {
+            GameMode::Classic
+            | GameMode::TimeAttack
+            | GameMode::Practice
+            | GameMode::Level
+            | GameMode::Playtest => None,
+            GameMode::DailyChallenge => Some(days_since_epoch()),
+        }
+    }
+}
+
+// Seed of the current course, picked on the ready screen. Random courses
+// get a fresh one each run so any run can be replayed from its seed.
+#[derive(Default)]
+pub struct CourseSeed(pub u64);
+
+pub fn pick_course_seed(mode: Res<GameMode>, mut seed: ResMut<CourseSeed>) {
+    seed.0 = mode
+        .course_seed()
+        .unwrap_or_else(|| rand::thread_rng().gen());
+}
+
+// Drives every random decision about the course layout so seeded modes
+// produce the same pipes for everyone.
+pub struct CourseRng(pub StdRng);
+
+impl CourseRng {
+    pub fn for_seed(seed: &CourseSeed) -> Self {
+        CourseRng(course_rng(seed, 0))
+    }
+}
+
+// Rng for one part of the course, seeded from the course seed. The salt
+// keeps its numbers from mirroring the pipe heights.
+pub fn course_rng(seed: &CourseSeed, salt: u64) -> StdRng {
+    StdRng::seed_from_u64(seed.0 ^ salt)
+}
+
+impl Default for CourseRng {
+    fn default() -> Self {
+        CourseRng(StdRng::from_entropy())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn days_since_epoch() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs() / SECONDS_PER_DAY)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn days_since_epoch() -> u64 {
+    (js_sys::Date::now() / 1000.0) as u64 / SECONDS_PER_DAY
+}
+
+// Puts the bird back into the gap of the nearest pipe column that has not
+// passed it yet and makes it briefly invulnerable.
+pub fn respawn_player(
+    commands: &mut Commands,
+    player_entity: Entity,
+    player: &mut Player,
+    transform: &mut Transform,
+    columns: impl Iterator<Item = Vec3>,
+) {
+    let gap_y = columns
+        .filter(|column| column.x + PIPE.x / 2.0 >= transform.translation.x)
+        .min_by(|a, b| a.x.total_cmp(&b.x))
+        .map_or(0.0, |column| column.y);
+
+    player.movement_speed = 0.0;
+    player.angle = 0.0;
+    transform.translation = vec3(PLAYER_POS_X, gap_y, transform.translation.z);
+    transform.rotation = Quat::IDENTITY;
+    commands
+        .entity(player_entity)
+        .insert(Invulnerable(Timer::from_seconds(
+            RESPAWN_INVULNERABILITY_SECS,
+            false,
+        )));
+}
+
+// Hitting the floor fades to game over on the spot, anything else sends the
+// bird diving to the floor first. The dive is shown as is, without a fade.
+pub fn end_run_on_collision(
+    mode: Res<GameMode>,
+    mut game_state: ResMut<State<GameState>>,
+    mut transition: ResMut<Transition>,
+    mut collision_events: EventReader<CollisionEvent>,
+) {
+    let obstacles: Vec<_> = collision_events
+        .iter()
+        .map(|event| event.obstacle)
+        .collect();
+    if obstacles.is_empty()
+        || !mode.ends_on_collision()
+        || *game_state.current() != GameState::Running
+        || transition.active()
+    {
+        return;
+    }
+
+    if obstacles.contains(&Obstacle::Floor) {
+        transition.fade_to(GameState::GameOver);
+    } else if let Err(error) = game_state.set(GameState::Dying) {
+        // a switch queued earlier this frame wins over the crash
+        debug!("crash ignored, {:?}", error);
+    }
+}
+
+pub struct ModesPlugin;
+
+impl Plugin for ModesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameMode>()
+            .init_resource::<CourseRng>()
+            .init_resource::<CourseSeed>()
+            .add_system_set(SystemSet::on_enter(GameState::Paused).with_system(pick_course_seed))
+            .add_system_set(running_set().with_system(end_run_on_collision));
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{
+    components::*,
+    save::SaveData,
+    settings::Settings,
+    time_scale::{running_set, WorldTime},
+};
+use bevy::{math::vec3, prelude::*};
+
+// copies of each layer laid side by side, enough to cover the screen while
+// one of them wraps around
+pub const BACKGROUND_TILES: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackgroundLayer {
+    Sky,
+    Clouds,
+    Skyline,
+    Bushes,
+}
+
+impl BackgroundLayer {
+    // back to front
+    pub const ALL: [BackgroundLayer; 4] = [
+        BackgroundLayer::Sky,
+        BackgroundLayer::Clouds,
+        BackgroundLayer::Skyline,
+        BackgroundLayer::Bushes,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            BackgroundLayer::Sky => "sky",
+            BackgroundLayer::Clouds => "clouds",
+            BackgroundLayer::Skyline => "skyline",
+            BackgroundLayer::Bushes => "bushes",
+        }
+    }
+
+    // fraction of the world speed the layer scrolls at
+    pub fn speed(self) -> f32 {
+        match self {
+            BackgroundLayer::Sky => 0.0,
+            BackgroundLayer::Clouds => 0.1,
+            BackgroundLayer::Skyline => 0.25,
+            BackgroundLayer::Bushes => 0.5,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Parallax {
+    pub layer: BackgroundLayer,
+    pub width: f32,
+}
+
+pub fn setup_background(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    save_data: Res<SaveData>,
+) {
+    let theme = save_data.theme_or_default(settings.theme);
+    let width = BACKGROUND_WIDTH * PLAYER_SCALE.x;
+    for (z, layer) in BackgroundLayer::ALL.iter().enumerate() {
+        let image = asset_server.load(&theme.background(*layer));
+        for n in 0..BACKGROUND_TILES {
+            commands
+                .spawn_bundle(SpriteBundle {
+                    texture: image.clone(),
+                    transform: Transform {
+                        translation: vec3(n as f32 * width, 0.0, z as f32 * 0.1),
+                        scale: PLAYER_SCALE,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .insert(Parallax {
+                    layer: *layer,
+                    width,
+                })
+                .insert(Background);
+        }
+    }
+}
+
+// Scrolls every layer at its own speed, wrapping tiles around like
+// `animate_world` does for the floor. The background stands still with
+// reduced motion.
+pub fn animate_parallax(
+    settings: Res<Settings>,
+    time: Res<WorldTime>,
+    mut query: Query<(&Parallax, &mut Transform)>,
+) {
+    if settings.accessibility.reduced_motion {
+        return;
+    }
+
+    for (parallax, mut transform) in query.iter_mut() {
+        let speed = parallax.layer.speed();
+        if speed == 0.0 {
+            continue;
+        }
+
+        transform.translation.x -= AUTO_MOVE_SPEED * speed * time.delta_seconds();
+        if transform.translation.x + parallax.width / 2.0 < -SCREEN.x / 2.0 {
+            transform.translation.x += parallax.width * BACKGROUND_TILES as f32;
+        }
+    }
+}
+
+pub struct ParallaxPlugin;
+
+impl Plugin for ParallaxPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_startup_system(setup_background)
+            .add_system_set(SystemSet::on_update(GameState::Paused).with_system(animate_parallax))
+            .add_system_set(running_set().with_system(animate_parallax));
+    }
+}
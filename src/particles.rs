@@ -0,0 +1,197 @@
+use crate::{
+    components::*,
+    time_scale::{running_set, WorldTime},
+};
+use bevy::{math::vec3, prelude::*};
+use rand::prelude::*;
+use serde::Deserialize;
+
+// above the bird
+const PARTICLE_Z: f32 = 2.5;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ParticleEffect {
+    pub sprite: String,
+    pub count: usize,
+    pub lifetime: f32,
+    pub speed: (f32, f32),
+    pub direction: f32,
+    pub spread: f32,
+    pub gravity: f32,
+    pub spin: f32,
+    pub scale: f32,
+}
+
+impl ParticleEffect {
+    // Mistakes the format can't rule out, which would otherwise only panic
+    // once the effect is spawned.
+    fn check(&self, name: &str) {
+        assert!(
+            self.speed.0 <= self.speed.1,
+            "assets/particles.ron: {} speed goes from {} down to {}",
+            name,
+            self.speed.0,
+            self.speed.1
+        );
+        assert!(
+            self.lifetime >= 0.0,
+            "assets/particles.ron: {} lifetime is negative",
+            name
+        );
+    }
+}
+
+// Loaded from `assets/particles.ron`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ParticleEffects {
+    pub flap: ParticleEffect,
+    pub score: ParticleEffect,
+    pub crash: ParticleEffect,
+}
+
+impl ParticleEffects {
+    pub fn load() -> Self {
+        let effects: Self = ron::from_str(include_str!("../assets/particles.ron"))
+            .expect("assets/particles.ron is not valid");
+        effects.flap.check("flap");
+        effects.score.check("score");
+        effects.crash.check("crash");
+        effects
+    }
+}
+
+#[derive(Component)]
+pub struct Particle {
+    pub velocity: Vec2,
+    pub gravity: f32,
+    // radians per second
+    pub spin: f32,
+    pub lifetime: Timer,
+}
+
+fn spawn_effect(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    rng: &mut impl Rng,
+    effect: &ParticleEffect,
+    position: Vec3,
+) {
+    let texture = asset_server.load(&effect.sprite);
+    for _ in 0..effect.count {
+        let angle = (effect.direction + rng.gen_range(-1.0..=1.0) * effect.spread).to_radians();
+        let speed = rng.gen_range(effect.speed.0..=effect.speed.1);
+        commands
+            .spawn_bundle(SpriteBundle {
+                texture: texture.clone(),
+                transform: Transform {
+                    translation: vec3(position.x, position.y, PARTICLE_Z),
+                    scale: vec3(effect.scale, effect.scale, 1.0),
+                    ..default()
+                },
+                ..default()
+            })
+            .insert(Particle {
+                velocity: Vec2::new(angle.cos(), angle.sin()) * speed,
+                gravity: effect.gravity,
+                spin: rng.gen_range(-1.0..=1.0) * effect.spin.to_radians(),
+                lifetime: Timer::from_seconds(effect.lifetime, false),
+            });
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_particles(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    time: Res<Time>,
+    // when the last crash burst went off
+    mut last_crash: Local<Option<f64>>,
+    effects: Res<ParticleEffects>,
+    mut flap_events: EventReader<FlapEvent>,
+    mut scored_events: EventReader<PointScoredEvent>,
+    mut collision_events: EventReader<CollisionEvent>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    let position = match player_query.get_single() {
+        Ok(transform) => transform.translation,
+        Err(_) => return,
+    };
+    let mut rng = thread_rng();
+
+    for _ in flap_events.iter() {
+        spawn_effect(
+            &mut commands,
+            &asset_server,
+            &mut rng,
+            &effects.flap,
+            position,
+        );
+    }
+    for _ in scored_events.iter() {
+        spawn_effect(
+            &mut commands,
+            &asset_server,
+            &mut rng,
+            &effects.score,
+            position,
+        );
+    }
+    // practice lets the bird scrape along a pipe, one burst per crash will do
+    let now = time.seconds_since_startup();
+    let bursting = last_crash.map_or(false, |at| now - at < effects.crash.lifetime as f64);
+    if collision_events.iter().count() > 0 && !bursting {
+        *last_crash = Some(now);
+        spawn_effect(
+            &mut commands,
+            &asset_server,
+            &mut rng,
+            &effects.crash,
+            position,
+        );
+    }
+}
+
+// Particles run on world time so they slow down and freeze with the world.
+pub fn update_particles(
+    mut commands: Commands,
+    time: Res<WorldTime>,
+    mut query: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+) {
+    let delta = time.delta_seconds();
+    for (entity, mut particle, mut transform, mut sprite) in query.iter_mut() {
+        particle.lifetime.tick(time.delta());
+        if particle.lifetime.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        particle.velocity.y += particle.gravity * delta;
+        transform.translation += particle.velocity.extend(0.0) * delta;
+        transform.rotate(Quat::from_rotation_z(particle.spin * delta));
+        sprite.color.set_a(1.0 - particle.lifetime.percent());
+    }
+}
+
+pub fn clear_particles(mut commands: Commands, query: Query<Entity, With<Particle>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+pub struct ParticlesPlugin;
+
+impl Plugin for ParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ParticleEffects::load())
+            .add_system(spawn_particles)
+            // debris keeps flying while the game over screen comes up
+            .add_system_set(running_set().with_system(update_particles))
+            .add_system_set(SystemSet::on_update(GameState::Dying).with_system(update_particles))
+            .add_system_set(SystemSet::on_update(GameState::GameOver).with_system(update_particles))
+            .add_system_set(
+                SystemSet::on_update(GameState::LevelComplete).with_system(update_particles),
+            )
+            .add_system_set(SystemSet::on_enter(GameState::Paused).with_system(clear_particles))
+            .add_system_set(SystemSet::on_enter(GameState::MainMenu).with_system(clear_particles));
+    }
+}
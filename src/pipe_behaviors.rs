@@ -0,0 +1,175 @@
+use crate::{coins::Coin, components::*};
+use bevy::prelude::*;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+
+// moving columns start showing up at this score
+pub const MOVING_PIPES_FROM_SCORE: usize = 5;
+pub const MOVING_PIPE_CHANCE_PER_POINT: f32 = 0.05;
+pub const MAX_MOVING_PIPE_CHANCE: f32 = 0.75;
+
+// Motion is driven by how far the column has scrolled rather than by time,
+// so it slows down with the world and replays identically after a rewind.
+pub const OSCILLATION_AMPLITUDE: f32 = PLAYER_HEIGHT * 1.5;
+pub const OSCILLATION_WAVELENGTH: f32 = SPACE_BETWEEN_PIPES * 2.0;
+// the gap grows and shrinks by this fraction of its size
+pub const GAP_BREATHING: f32 = 0.3;
+pub const GAP_WAVELENGTH: f32 = SPACE_BETWEEN_PIPES;
+// drop-in pipes fall a screen height and have landed once the column
+// reaches this x
+pub const DROP_IN_LANDED_X: f32 = PIPE_WIDTH * 2.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum PipeBehavior {
+    #[default]
+    Static,
+    // the whole column bobs up and down
+    Oscillating,
+    // the gap slowly opens and closes
+    Breathing,
+    // the pipes fall into place as the column comes in
+    DropIn,
+}
+
+impl PipeBehavior {
+    pub const MOVING: [PipeBehavior; 3] = [
+        PipeBehavior::Oscillating,
+        PipeBehavior::Breathing,
+        PipeBehavior::DropIn,
+    ];
+}
+
+// Which way a pipe of a column points, 1 for the top pipe and -1 for the
+// bottom one.
+#[derive(Component)]
+pub struct PipeHalf(pub f32);
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct PipeMotion {
+    pub behavior: PipeBehavior,
+    // column x when the behavior was picked
+    pub origin_x: f32,
+    // size of the gap while it isn't animated
+    pub gap_size: f32,
+    // vertical offset of the gap the coin has been moved by
+    pub gap_offset: f32,
+}
+
+impl Default for PipeMotion {
+    fn default() -> Self {
+        PipeMotion {
+            behavior: PipeBehavior::Static,
+            origin_x: 0.0,
+            gap_size: VERTICAL_SPACE_BETWEEN_PIPES,
+            gap_offset: 0.0,
+        }
+    }
+}
+
+pub fn moving_pipe_chance(score: usize) -> f32 {
+    let points = score.saturating_sub(MOVING_PIPES_FROM_SCORE) as f32;
+    (points * MOVING_PIPE_CHANCE_PER_POINT).min(MAX_MOVING_PIPE_CHANCE)
+}
+
+impl PipeMotion {
+    // Called whenever a column is placed at random, picks how it moves on
+    // this pass.
+    pub fn assign(&mut self, rng: &mut impl Rng, score: usize, x: f32) {
+        let behavior = if rng.gen::<f32>() < moving_pipe_chance(score) {
+            *PipeBehavior::MOVING.choose(rng).unwrap()
+        } else {
+            PipeBehavior::Static
+        };
+        self.place(behavior, VERTICAL_SPACE_BETWEEN_PIPES, x);
+    }
+
+    pub fn place(&mut self, behavior: PipeBehavior, gap_size: f32, x: f32) {
+        self.behavior = behavior;
+        self.gap_size = gap_size;
+        self.origin_x = x;
+    }
+
+    // scrolled distance since the behavior was picked
+    fn travelled(&self, x: f32) -> f32 {
+        self.origin_x - x
+    }
+
+    // offset of the gap center, coins follow it
+    fn gap_offset_at(&self, x: f32) -> f32 {
+        match self.behavior {
+            PipeBehavior::Oscillating => {
+                OSCILLATION_AMPLITUDE * (TAU * self.travelled(x) / OSCILLATION_WAVELENGTH).sin()
+            }
+            _ => 0.0,
+        }
+    }
+
+    fn gap_size_at(&self, x: f32) -> f32 {
+        match self.behavior {
+            PipeBehavior::Breathing => {
+                let phase = (TAU * self.travelled(x) / GAP_WAVELENGTH).sin();
+                self.gap_size * (1.0 + GAP_BREATHING * phase)
+            }
+            _ => self.gap_size,
+        }
+    }
+
+    // center offset and size of the gap to fly through once the column has
+    // scrolled to x
+    pub fn gap_at(&self, x: f32) -> (f32, f32) {
+        (
+            self.gap_offset_at(x) + self.drop_offset_at(x),
+            self.gap_size_at(x),
+        )
+    }
+
+    // offset of the pipes only, the coin waits in the gap
+    fn drop_offset_at(&self, x: f32) -> f32 {
+        match self.behavior {
+            PipeBehavior::DropIn => {
+                // start falling as the column enters the screen
+                let entering_x = SCREEN.x / 2.0 + PIPE.x / 2.0;
+                let progress = (x - DROP_IN_LANDED_X) / (entering_x - DROP_IN_LANDED_X);
+                SCREEN.y * progress.clamp(0.0, 1.0).powi(2)
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+pub fn pipe_translation_y(half: f32, gap_size: f32) -> f32 {
+    half * (PIPE.y / 2.0 + gap_size / 2.0)
+}
+
+// Moves the pipes of every column according to its behavior. The pipes are
+// the blockers, so collision follows them.
+#[allow(clippy::type_complexity)]
+pub fn animate_pipes(
+    mut column_query: Query<(&mut PipeMotion, &Transform, &Children)>,
+    mut pipe_query: Query<(&PipeHalf, &mut Transform), Without<PipeMotion>>,
+    mut coin_query: Query<&mut Transform, (With<Coin>, Without<PipeHalf>, Without<PipeMotion>)>,
+) {
+    for (mut motion, column_transform, children) in column_query.iter_mut() {
+        let x = column_transform.translation.x;
+        let gap_offset = motion.gap_offset_at(x);
+        let gap_size = motion.gap_size_at(x);
+        let drop_offset = motion.drop_offset_at(x);
+
+        for child in children.iter() {
+            if let Ok((half, mut transform)) = pipe_query.get_mut(*child) {
+                let y = pipe_translation_y(half.0, gap_size) + gap_offset + drop_offset;
+                if transform.translation.y != y {
+                    transform.translation.y = y;
+                }
+            } else if let Ok(mut transform) = coin_query.get_mut(*child) {
+                // relative, the magnet may have moved the coin
+                transform.translation.y += gap_offset - motion.gap_offset;
+            }
+        }
+
+        if motion.gap_offset != gap_offset {
+            motion.gap_offset = gap_offset;
+        }
+    }
+}
@@ -0,0 +1,426 @@
+use crate::{
+    coins::Coin,
+    components::*,
+    modes::{course_rng, pick_course_seed, CourseSeed, GameMode},
+    time_scale::{running_set, GameSpeed, WorldTime},
+};
+use bevy::{
+    math::{const_vec2, const_vec3, vec3},
+    prelude::*,
+    sprite::collide_aabb::collide,
+};
+use rand::{distributions::WeightedIndex, prelude::*, rngs::StdRng};
+use serde::Deserialize;
+
+// sprites are 16x16, drawn at twice the size
+pub const POWER_UP: Vec2 = const_vec2!([32.0, 32.0]);
+// pickups sit halfway between a column and the one before it
+pub const POWER_UP_POSITION: Vec3 = const_vec3!([-SPACE_BETWEEN_PIPES / 2.0, 0.0, 0.5]);
+// how long the bird can't be hit after a shield breaks
+pub const SHIELD_BREAK_INVULNERABILITY_SECS: f32 = 1.0;
+pub const MAGNET_SPEED: f32 = 600.0;
+const POWER_UP_SEED_SALT: u64 = 0x5eed_b00f;
+const HUD_ROW_HEIGHT: f32 = 44.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum PowerUpEffect {
+    // absorbs the next hit
+    Shield,
+    // scales the bird and its hitbox
+    Shrink { scale: f32 },
+    // scales the speed of the world
+    SlowMo { speed: f32 },
+    // pulls in coins closer than the radius
+    Magnet { radius: f32 },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PowerUpDefinition {
+    pub name: String,
+    pub icon: String,
+    pub duration: f32,
+    pub weight: f32,
+    pub effect: PowerUpEffect,
+}
+
+// Loaded from `assets/power_ups.ron`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PowerUpDefinitions {
+    pub spawn_chance: f64,
+    pub power_ups: Vec<PowerUpDefinition>,
+}
+
+impl PowerUpDefinitions {
+    pub fn load() -> Self {
+        ron::from_str(include_str!("../assets/power_ups.ron"))
+            .expect("assets/power_ups.ron is not valid")
+    }
+}
+
+pub struct PowerUpIcons(pub Vec<Handle<Image>>);
+
+// Rolls which power-ups spawn, seeded like the course so daily challenges
+// hand out the same ones.
+pub struct PowerUpRng(pub StdRng);
+
+impl Default for PowerUpRng {
+    fn default() -> Self {
+        PowerUpRng(StdRng::from_entropy())
+    }
+}
+
+pub struct ActivePowerUp {
+    pub definition: usize,
+    pub effect: PowerUpEffect,
+    pub timer: Timer,
+}
+
+#[derive(Default)]
+pub struct ActivePowerUps(pub Vec<ActivePowerUp>);
+
+impl ActivePowerUps {
+    // picking up an active power-up again restarts its timer
+    pub fn activate(&mut self, index: usize, definition: &PowerUpDefinition) {
+        self.0.retain(|active| active.definition != index);
+        self.0.push(ActivePowerUp {
+            definition: index,
+            effect: definition.effect,
+            timer: Timer::from_seconds(definition.duration, false),
+        });
+    }
+
+    // returns true if a shield was used up
+    pub fn consume_shield(&mut self) -> bool {
+        let shield = self
+            .0
+            .iter()
+            .position(|active| active.effect == PowerUpEffect::Shield);
+        match shield {
+            Some(index) => {
+                self.0.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn shrink_scale(&self) -> f32 {
+        self.0
+            .iter()
+            .map(|active| match active.effect {
+                PowerUpEffect::Shrink { scale } => scale,
+                _ => 1.0,
+            })
+            .product()
+    }
+
+    pub fn world_speed(&self) -> f32 {
+        self.0
+            .iter()
+            .map(|active| match active.effect {
+                PowerUpEffect::SlowMo { speed } => speed,
+                _ => 1.0,
+            })
+            .product()
+    }
+
+    pub fn magnet_radius(&self) -> Option<f32> {
+        self.0
+            .iter()
+            .filter_map(|active| match active.effect {
+                PowerUpEffect::Magnet { radius } => Some(radius),
+                _ => None,
+            })
+            .reduce(f32::max)
+    }
+
+    pub fn player_hitbox(&self) -> Vec2 {
+        PLAYER * self.shrink_scale()
+    }
+}
+
+#[derive(Component)]
+pub struct PowerUpPickup {
+    // index into the definitions, None while nothing is on offer
+    pub definition: Option<usize>,
+}
+
+#[derive(Component)]
+pub struct PowerUpIcon(pub usize);
+
+#[derive(Component)]
+pub struct PowerUpTimerText(pub usize);
+
+pub fn setup_power_ups(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    definitions: Res<PowerUpDefinitions>,
+    column_query: Query<Entity, (With<AutoMoving>, With<Countable>)>,
+) {
+    let icons: Vec<Handle<Image>> = definitions
+        .power_ups
+        .iter()
+        .map(|definition| asset_server.load(definition.icon.as_str()))
+        .collect();
+
+    for column in column_query.iter() {
+        let pickup = commands
+            .spawn_bundle(SpriteBundle {
+                transform: Transform {
+                    translation: POWER_UP_POSITION,
+                    scale: vec3(2.0, 2.0, 1.0),
+                    ..default()
+                },
+                visibility: Visibility { is_visible: false },
+                ..default()
+            })
+            .insert(PowerUpPickup { definition: None })
+            .id();
+        commands.entity(column).add_child(pickup);
+    }
+
+    let style = TextStyle {
+        font: asset_server.load("flappy-font.ttf"),
+        font_size: 32.0,
+        color: Color::WHITE,
+    };
+    let alignment = TextAlignment {
+        vertical: VerticalAlign::Center,
+        horizontal: HorizontalAlign::Left,
+    };
+    for (index, icon) in icons.iter().enumerate() {
+        commands
+            .spawn_bundle(SpriteBundle {
+                texture: icon.clone(),
+                transform: Transform {
+                    translation: vec3(0.0, 0.0, 11.0),
+                    scale: vec3(2.0, 2.0, 1.0),
+                    ..default()
+                },
+                visibility: Visibility { is_visible: false },
+                ..default()
+            })
+            .insert(PowerUpIcon(index));
+        commands
+            .spawn_bundle(Text2dBundle {
+                text: Text::with_section("", style.clone(), alignment),
+                visibility: Visibility { is_visible: false },
+                ..default()
+            })
+            .insert(PowerUpTimerText(index));
+    }
+
+    commands.insert_resource(PowerUpIcons(icons));
+}
+
+pub fn reset_power_ups(
+    seed: Res<CourseSeed>,
+    mut rng: ResMut<PowerUpRng>,
+    mut active: ResMut<ActivePowerUps>,
+) {
+    *rng = PowerUpRng(course_rng(&seed, POWER_UP_SEED_SALT));
+    active.0.clear();
+}
+
+pub fn clear_power_ups(mut active: ResMut<ActivePowerUps>) {
+    active.0.clear();
+}
+
+// Decides what the pickup before a column offers whenever the column is laid
+// out. Levels name their power-ups, other modes roll for one.
+pub fn place_power_ups(
+    mode: Res<GameMode>,
+    definitions: Res<PowerUpDefinitions>,
+    icons: Res<PowerUpIcons>,
+    mut rng: ResMut<PowerUpRng>,
+    mut column_events: EventReader<ColumnPlacedEvent>,
+    mut query: Query<(
+        &mut PowerUpPickup,
+        &mut Handle<Image>,
+        &mut Transform,
+        &mut Visibility,
+        &Parent,
+    )>,
+) {
+    let weights = WeightedIndex::new(definitions.power_ups.iter().map(|d| d.weight)).ok();
+
+    for placed in column_events.iter() {
+        let definition = match &placed.power_up {
+            Some(name) => {
+                let index = definitions.power_ups.iter().position(|d| &d.name == name);
+                if index.is_none() {
+                    warn!("unknown power-up {}", name);
+                }
+                index
+            }
+            None if mode.plays_level() => None,
+            None => match &weights {
+                Some(weights) if rng.0.gen_bool(definitions.spawn_chance) => {
+                    Some(weights.sample(&mut rng.0))
+                }
+                _ => None,
+            },
+        };
+
+        for (mut pickup, mut texture, mut transform, mut visibility, column) in query.iter_mut() {
+            if column.0 != placed.column {
+                continue;
+            }
+            pickup.definition = definition;
+            if let Some(index) = definition {
+                *texture = icons.0[index].clone();
+            }
+            // halfway to the column before
+            transform.translation.x = -placed.spacing / 2.0;
+            visibility.is_visible = definition.is_some();
+        }
+    }
+}
+
+pub fn collect_power_ups(
+    definitions: Res<PowerUpDefinitions>,
+    mut active: ResMut<ActivePowerUps>,
+    player_query: Query<&GlobalTransform, With<Collider>>,
+    mut pickup_query: Query<(&mut PowerUpPickup, &mut Visibility, &GlobalTransform)>,
+) {
+    let hitbox = active.player_hitbox();
+    for player_transform in player_query.iter() {
+        for (mut pickup, mut visibility, transform) in pickup_query.iter_mut() {
+            let index = match pickup.definition {
+                Some(index) => index,
+                None => continue,
+            };
+
+            let hit = collide(
+                player_transform.translation,
+                hitbox,
+                transform.translation,
+                POWER_UP,
+            );
+            if hit.is_some() {
+                pickup.definition = None;
+                visibility.is_visible = false;
+                active.activate(index, &definitions.power_ups[index]);
+            }
+        }
+    }
+}
+
+// Power-ups run on real time so slow-mo doesn't stretch itself.
+pub fn tick_power_ups(time: Res<Time>, mut active: ResMut<ActivePowerUps>) {
+    for power_up in active.0.iter_mut() {
+        power_up.timer.tick(time.delta());
+    }
+    if active.0.iter().any(|power_up| power_up.timer.finished()) {
+        active.0.retain(|power_up| !power_up.timer.finished());
+    }
+}
+
+pub fn apply_power_ups(
+    active: Res<ActivePowerUps>,
+    mut game_speed: ResMut<GameSpeed>,
+    mut player_query: Query<&mut Transform, With<Player>>,
+) {
+    if !active.is_changed() {
+        return;
+    }
+
+    let speed = active.world_speed();
+    if game_speed.power_up != speed {
+        game_speed.power_up = speed;
+    }
+
+    let scale = active.shrink_scale();
+    for mut transform in player_query.iter_mut() {
+        transform.scale = vec3(BIRD_SIZE.x * scale, BIRD_SIZE.y * scale, BIRD_SIZE.z);
+    }
+}
+
+pub fn magnet_system(
+    time: Res<WorldTime>,
+    active: Res<ActivePowerUps>,
+    player_query: Query<&GlobalTransform, With<Player>>,
+    mut coin_query: Query<(&Coin, &mut Transform, &GlobalTransform)>,
+) {
+    let radius = match active.magnet_radius() {
+        Some(radius) => radius,
+        None => return,
+    };
+    let player = player_query.single().translation;
+
+    for (coin, mut transform, global_transform) in coin_query.iter_mut() {
+        let offset = (player - global_transform.translation).truncate();
+        let distance = offset.length();
+        if coin.collected || distance > radius || distance == 0.0 {
+            continue;
+        }
+
+        let step = (MAGNET_SPEED * time.delta_seconds()).min(distance);
+        transform.translation += (offset / distance * step).extend(0.0);
+    }
+}
+
+pub fn update_power_up_hud(
+    active: Res<ActivePowerUps>,
+    mut icon_query: Query<(&PowerUpIcon, &mut Transform, &mut Visibility)>,
+    mut text_query: Query<
+        (
+            &PowerUpTimerText,
+            &mut Text,
+            &mut Transform,
+            &mut Visibility,
+        ),
+        Without<PowerUpIcon>,
+    >,
+) {
+    let top_right = vec3(SCREEN.x / 2.0 - 110.0, SCREEN.y / 2.0 - 40.0, 11.0);
+    let row = |definition: usize| {
+        active
+            .0
+            .iter()
+            .position(|power_up| power_up.definition == definition)
+    };
+
+    for (icon, mut transform, mut visibility) in icon_query.iter_mut() {
+        visibility.is_visible = row(icon.0).is_some();
+        if let Some(row) = row(icon.0) {
+            transform.translation = top_right - vec3(0.0, row as f32 * HUD_ROW_HEIGHT, 0.0);
+        }
+    }
+
+    for (timer_text, mut text, mut transform, mut visibility) in text_query.iter_mut() {
+        visibility.is_visible = row(timer_text.0).is_some();
+        if let Some(row) = row(timer_text.0) {
+            let timer = &active.0[row].timer;
+            let remaining = timer.duration().as_secs_f32() - timer.elapsed_secs();
+            text.sections[0].value = format!("{:.1}", remaining.max(0.0));
+            transform.translation = top_right + vec3(28.0, -(row as f32) * HUD_ROW_HEIGHT, 0.0);
+        }
+    }
+}
+
+pub struct PowerUpsPlugin;
+
+impl Plugin for PowerUpsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PowerUpDefinitions::load())
+            .init_resource::<PowerUpRng>()
+            .init_resource::<ActivePowerUps>()
+            .add_startup_system_to_stage(StartupStage::PostStartup, setup_power_ups)
+            .add_system_set(
+                SystemSet::on_enter(GameState::Paused)
+                    .with_system(reset_power_ups.after(pick_course_seed)),
+            )
+            .add_system_set(
+                running_set()
+                    .with_system(collect_power_ups)
+                    .with_system(tick_power_ups)
+                    .with_system(magnet_system),
+            )
+            .add_system_set(SystemSet::on_enter(GameState::GameOver).with_system(clear_power_ups))
+            // after the course systems in `Update` have laid the columns out
+            .add_system_to_stage(CoreStage::PostUpdate, place_power_ups)
+            .add_system(apply_power_ups)
+            .add_system(update_power_up_hud);
+    }
+}
@@ -0,0 +1,277 @@
+use crate::{
+    components::*,
+    hazards::{Hazard, HazardRng},
+    input::back_just_pressed,
+    locale::{Localization, LocalizedFont},
+    modes::{CourseRng, GameMode, RESPAWN_INVULNERABILITY_SECS},
+    pipe_behaviors::PipeMotion,
+    settings::Settings,
+    time_scale::{clamp_game_speed, running_set, GAME_SPEED_STEP},
+    transitions::Transition,
+};
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+
+pub const HIT_FLASH_SECS: f32 = 0.25;
+const HIT_FLASH_COLOR: Color = Color::rgb(1.0, 0.3, 0.3);
+
+// Snapshot of the course taken whenever the bird passes a pipe so a crash
+// can rewind to it with the same layout ahead. Hazards on screen are cleared
+// on a rewind, the ones after it come out the same again.
+pub struct Checkpoint {
+    score: usize,
+    player_y: f32,
+    columns: Vec<(Entity, Vec3, bool, PipeMotion)>,
+    rng: StdRng,
+    hazard_rng: StdRng,
+}
+
+#[derive(Default)]
+pub struct PracticeCheckpoint(pub Option<Checkpoint>);
+
+// Tints the bird while it is overlapping a blocker in no-crash practice.
+#[derive(Component)]
+pub struct HitFlash(pub Timer);
+
+#[derive(Component)]
+pub struct PracticeText;
+
+fn practice_label(localization: &Localization, settings: &Settings) -> String {
+    let crash = if settings.practice.invulnerable {
+        "practice-no-crash"
+    } else {
+        "practice-checkpoints"
+    };
+    let percent = (clamp_game_speed(settings.practice.game_speed) * 100.0).round();
+    localization.format(
+        "practice-label",
+        &[
+            ("mode", localization.get(crash)),
+            ("percent", percent.to_string()),
+        ],
+    )
+}
+
+pub fn setup_practice_text(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    localization: Res<Localization>,
+) {
+    let style = TextStyle {
+        font: localization.font(&asset_server),
+        font_size: 32.0,
+        color: Color::WHITE,
+    };
+    let alignment = TextAlignment {
+        vertical: VerticalAlign::Center,
+        horizontal: HorizontalAlign::Center,
+    };
+
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section("", style, alignment),
+            transform: Transform::from_xyz(0.0, SCREEN.y / 2.0 - 40.0, 11.0),
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(PracticeText)
+        .insert(LocalizedFont);
+}
+
+pub fn reset_practice(
+    mode: Res<GameMode>,
+    mut checkpoint: ResMut<PracticeCheckpoint>,
+    mut query: Query<&mut Visibility, With<PracticeText>>,
+) {
+    checkpoint.0 = None;
+    for mut visibility in query.iter_mut() {
+        visibility.is_visible = *mode == GameMode::Practice;
+    }
+}
+
+pub fn update_practice_text(
+    localization: Res<Localization>,
+    settings: Res<Settings>,
+    mut query: Query<&mut Text, With<PracticeText>>,
+) {
+    if !settings.is_changed() && !localization.is_changed() {
+        return;
+    }
+
+    for mut text in query.iter_mut() {
+        text.sections[0].value = practice_label(&localization, &settings);
+    }
+}
+
+pub fn handle_practice_input(
+    mode: Res<GameMode>,
+    mut settings: ResMut<Settings>,
+    mut transition: ResMut<Transition>,
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_input: Res<Input<GamepadButton>>,
+) {
+    if *mode != GameMode::Practice {
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::I) {
+        settings.practice.invulnerable = !settings.practice.invulnerable;
+    }
+    if keyboard_input.just_pressed(KeyCode::Minus) {
+        settings.practice.game_speed =
+            clamp_game_speed(settings.practice.game_speed - GAME_SPEED_STEP);
+    }
+    if keyboard_input.just_pressed(KeyCode::Equals) {
+        settings.practice.game_speed =
+            clamp_game_speed(settings.practice.game_speed + GAME_SPEED_STEP);
+    }
+
+    // practice never ends by itself
+    if back_just_pressed(&keyboard_input, &gamepads, &gamepad_input) {
+        transition.fade_to(GameState::GameOver);
+    }
+}
+
+pub fn practice_checkpoint_system(
+    mode: Res<GameMode>,
+    scoreboard: Res<Scoreboard>,
+    course_rng: Res<CourseRng>,
+    hazard_rng: Res<HazardRng>,
+    mut checkpoint: ResMut<PracticeCheckpoint>,
+    player_query: Query<&Transform, With<Player>>,
+    column_query: Query<(Entity, &Transform, &Countable, &PipeMotion), With<AutoMoving>>,
+) {
+    if *mode != GameMode::Practice {
+        return;
+    }
+
+    let due = match &checkpoint.0 {
+        Some(checkpoint) => checkpoint.score != scoreboard.score,
+        None => true,
+    };
+    if !due {
+        return;
+    }
+
+    checkpoint.0 = Some(Checkpoint {
+        score: scoreboard.score,
+        player_y: player_query.single().translation.y,
+        columns: column_query
+            .iter()
+            .map(|(entity, transform, countable, motion)| {
+                (entity, transform.translation, countable.0, *motion)
+            })
+            .collect(),
+        rng: course_rng.0.clone(),
+        hazard_rng: hazard_rng.0.clone(),
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn practice_crash_system(
+    mut commands: Commands,
+    mode: Res<GameMode>,
+    settings: Res<Settings>,
+    checkpoint: Res<PracticeCheckpoint>,
+    mut scoreboard: ResMut<Scoreboard>,
+    mut course_rng: ResMut<CourseRng>,
+    mut hazard_rng: ResMut<HazardRng>,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut player_query: Query<(Entity, &mut Player, &mut Transform)>,
+    mut column_query: Query<(&mut Transform, &mut Countable, &mut PipeMotion), Without<Player>>,
+    hazard_query: Query<Entity, With<Hazard>>,
+) {
+    if *mode != GameMode::Practice || collision_events.iter().count() == 0 {
+        return;
+    }
+
+    let (entity, mut player, mut transform) = player_query.single_mut();
+    if settings.practice.invulnerable {
+        commands
+            .entity(entity)
+            .insert(HitFlash(Timer::from_seconds(HIT_FLASH_SECS, false)));
+        return;
+    }
+
+    let checkpoint = match &checkpoint.0 {
+        Some(checkpoint) => checkpoint,
+        None => return,
+    };
+
+    scoreboard.score = checkpoint.score;
+    course_rng.0 = checkpoint.rng.clone();
+    hazard_rng.0 = checkpoint.hazard_rng.clone();
+    for hazard in hazard_query.iter() {
+        commands.entity(hazard).despawn();
+    }
+    for (column, translation, counted, saved_motion) in checkpoint.columns.iter() {
+        if let Ok((mut column_transform, mut countable, mut motion)) = column_query.get_mut(*column)
+        {
+            column_transform.translation = *translation;
+            countable.0 = *counted;
+            // the coin sits where the current gap offset put it, keep that
+            motion.behavior = saved_motion.behavior;
+            motion.origin_x = saved_motion.origin_x;
+        }
+    }
+
+    player.movement_speed = 0.0;
+    player.angle = 0.0;
+    transform.translation.y = checkpoint.player_y;
+    transform.rotation = Quat::IDENTITY;
+    commands
+        .entity(entity)
+        .insert(Invulnerable(Timer::from_seconds(
+            RESPAWN_INVULNERABILITY_SECS,
+            false,
+        )));
+}
+
+pub fn hit_flash_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut HitFlash, &mut TextureAtlasSprite)>,
+) {
+    for (entity, mut flash, mut sprite) in query.iter_mut() {
+        flash.0.tick(time.delta());
+        if flash.0.finished() {
+            sprite.color = Color::WHITE;
+            commands.entity(entity).remove::<HitFlash>();
+        } else {
+            sprite.color = HIT_FLASH_COLOR;
+        }
+    }
+}
+
+pub fn clear_hit_flash(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut TextureAtlasSprite), With<HitFlash>>,
+) {
+    for (entity, mut sprite) in query.iter_mut() {
+        sprite.color = Color::WHITE;
+        commands.entity(entity).remove::<HitFlash>();
+    }
+}
+
+pub struct PracticePlugin;
+
+impl Plugin for PracticePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PracticeCheckpoint>()
+            .add_startup_system(setup_practice_text)
+            .add_system(update_practice_text)
+            .add_system_set(
+                SystemSet::on_enter(GameState::Paused)
+                    .with_system(reset_practice)
+                    .with_system(clear_hit_flash),
+            )
+            .add_system_set(
+                running_set()
+                    .with_system(handle_practice_input)
+                    .with_system(practice_checkpoint_system)
+                    .with_system(practice_crash_system)
+                    .with_system(hit_flash_system),
+            );
+    }
+}
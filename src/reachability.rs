@@ -0,0 +1,351 @@
+use crate::{components::*, pipe_behaviors::PipeMotion};
+use rand::prelude::*;
+
+// the bird aims this far inside the reach it has between two gaps
+pub const REACH_MARGIN: f32 = PLAYER_HEIGHT / 2.0;
+
+// A column already on the course, the next one has to be reachable from it.
+#[derive(Debug, Clone, Copy)]
+pub struct PlacedColumn {
+    pub x: f32,
+    pub y: f32,
+    pub motion: PipeMotion,
+}
+
+impl PlacedColumn {
+    // center of the gap once the column has scrolled to x
+    pub fn gap_center_at(&self, x: f32) -> f32 {
+        self.y + self.motion.gap_at(x).0
+    }
+}
+
+// One frame of gravity, as applied by `player_movement_system`.
+pub fn fall_speed(speed: f32, gravity: f32, delta_seconds: f32) -> f32 {
+    (speed + gravity * delta_seconds).clamp(-MAX_FALL_SPEED, MAX_RISE_SPEED)
+}
+
+// Column x at which the bird starts overlapping it.
+pub fn entry_x() -> f32 {
+    PLAYER_POS_X + (PIPE.x + PLAYER.x) / 2.0
+}
+
+// Column x at which the bird is clear of it.
+pub fn exit_x() -> f32 {
+    PLAYER_POS_X - (PIPE.x + PLAYER.x) / 2.0
+}
+
+// Height of the straight route from the gap of `previous` to the gap of
+// `next` where the bird is, once the course has scrolled by `scrolled`. It
+// keeps to the gap center while the bird is inside either column.
+pub fn route_y(previous: &PlacedColumn, next: &PlacedColumn, scrolled: f32) -> f32 {
+    let previous_x = previous.x - scrolled;
+    let next_x = next.x - scrolled;
+    if previous_x >= exit_x() {
+        return previous.gap_center_at(previous_x);
+    }
+    if next_x <= entry_x() {
+        return next.gap_center_at(next_x);
+    }
+
+    let from = previous.gap_center_at(exit_x());
+    let to = next.gap_center_at(entry_x());
+    let progress = (exit_x() - previous_x) / (exit_x() - previous_x + next_x - entry_x());
+    from + (to - from) * progress
+}
+
+// How far the bird can climb and drop over `frames` frames, starting from a
+// flap: flapping every frame or not at all.
+pub fn reach(frames: usize) -> (f32, f32) {
+    let fly = |flapping: bool| {
+        let (mut y, mut speed) = (0.0, JUMP_SPEED);
+        for _ in 0..frames {
+            if flapping {
+                speed = JUMP_SPEED;
+            }
+            speed = fall_speed(speed, SCALED_GRAVITY, TIME_STEP);
+            y += speed * TIME_STEP;
+        }
+        y
+    };
+    (fly(true), -fly(false))
+}
+
+// Moves the gap of a column placed at x as little as possible for the bird
+// to make it there from the gap of `previous`.
+pub fn reachable_gap_y(previous: &PlacedColumn, x: f32, y: f32, motion: &PipeMotion) -> f32 {
+    let between = x - previous.x - PIPE.x - PLAYER.x;
+    let frames = (between / AUTO_MOVE_SPEED / TIME_STEP).max(0.0) as usize;
+    let (climb, drop) = reach(frames);
+
+    let from = previous.gap_center_at(exit_x());
+    let offset = motion.gap_at(entry_x()).0;
+    let lowest = from - (drop - REACH_MARGIN).max(0.0);
+    let highest = from + (climb - REACH_MARGIN).max(0.0);
+    (y + offset).clamp(lowest, highest) - offset
+}
+
+// Picks the gap and behavior of a column placed at random at x.
+pub fn random_gap_y(
+    rng: &mut impl Rng,
+    auto_moving: &AutoMoving,
+    score: usize,
+    x: f32,
+    previous: Option<&PlacedColumn>,
+    motion: &mut PipeMotion,
+) -> f32 {
+    let y = auto_moving.initial.y + auto_moving.randomness.y * rng.gen_range(-1.0..1.0);
+    motion.assign(rng, score, x);
+    match previous {
+        Some(previous) => reachable_gap_y(previous, x, y, motion),
+        None => y,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hazards::roll_hazard, pipe_behaviors::pipe_translation_y, setup::pipe_start_translation,
+        transitions::FADE_SECS,
+    };
+    use bevy::math::{vec3, Vec2, Vec3};
+    use rand::rngs::StdRng;
+    use std::collections::HashSet;
+
+    const SEEDS: u64 = 2000;
+    const COLUMNS: usize = 60;
+    const HAZARD_SEED_SALT: u64 = 0x4a2a_2d00;
+    // the search first tries flapping once the bird sinks this far below the
+    // gap it aims for
+    const FLAP_BELOW: f32 = PLAYER_HEIGHT / 2.0;
+    // heights this close together at the same speed count as the same state
+    const SEARCH_CELL: f32 = 0.5;
+
+    // as spawned by `setup_pipes`
+    fn auto_moving(randomness: f32) -> AutoMoving {
+        AutoMoving {
+            width: PIPE.x * 2.0,
+            displacement: SPACE_BETWEEN_PIPES / 2.0,
+            randomness: vec3(0.0, randomness, 0.0),
+            initial: Vec3::ZERO,
+        }
+    }
+
+    // lowest the bird can fly without touching the floor
+    fn lowest_y() -> f32 {
+        FLOOR_POS + FLOOR.y * 1.2 / 2.0 + PLAYER.y / 2.0
+    }
+
+    // One frame of the course as far as the bird is concerned.
+    struct Frame {
+        // heights at which the bird hits something, as open ranges
+        blocked: Vec<(f32, f32)>,
+        // center of the gap the bird is in or flies into next
+        target: f32,
+        score: usize,
+    }
+
+    impl Frame {
+        fn block(&mut self, x: f32, y: f32, size: Vec2) {
+            if (x - PLAYER_POS_X).abs() < (size.x + PLAYER.x) / 2.0 {
+                let reach = (size.y + PLAYER.y) / 2.0;
+                self.blocked.push((y - reach, y + reach));
+            }
+        }
+
+        fn is_clear(&self, y: f32) -> bool {
+            self.blocked
+                .iter()
+                .all(|(below, above)| y <= *below || y >= *above)
+        }
+    }
+
+    // Lays out a seeded course frame by frame the way the game does, pipes,
+    // birds and balloons, until the bird would have passed `COLUMNS` pipes.
+    // Also returns how many hazards were placed.
+    fn course(seed: u64, auto_moving: &AutoMoving) -> (Vec<Frame>, usize) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut hazard_rng = StdRng::seed_from_u64(seed ^ HAZARD_SEED_SALT);
+        let mut columns: Vec<PlacedColumn> = (0..2)
+            .map(|n| {
+                let translation = pipe_start_translation(n, &mut rng);
+                let mut motion = PipeMotion::default();
+                motion.assign(&mut rng, 0, translation.x);
+                PlacedColumn {
+                    x: translation.x,
+                    y: translation.y,
+                    motion,
+                }
+            })
+            .collect();
+        let mut countable = [true, true];
+        let mut hazards = Vec::new();
+        let mut placed_hazards = 0;
+        let mut frames = Vec::new();
+        let mut score = 0;
+        let step = AUTO_MOVE_SPEED * TIME_STEP;
+
+        while score < COLUMNS {
+            let mut last = columns
+                .iter()
+                .map(|column| PlacedColumn {
+                    x: column.x - step,
+                    ..*column
+                })
+                .max_by(|a, b| a.x.total_cmp(&b.x));
+            for (n, column) in columns.iter_mut().enumerate() {
+                column.x -= step;
+                if column.x + auto_moving.width / 2.0 < -SCREEN.x / 2.0 {
+                    countable[n] = true;
+                    column.x = SCREEN.x / 2.0 + auto_moving.width / 2.0 + auto_moving.displacement;
+                    column.y = random_gap_y(
+                        &mut rng,
+                        auto_moving,
+                        score,
+                        column.x,
+                        last.as_ref(),
+                        &mut column.motion,
+                    );
+                    if let Some(previous) = last {
+                        let rolled = roll_hazard(
+                            &mut hazard_rng,
+                            score,
+                            &previous,
+                            column,
+                            SPACE_BETWEEN_PIPES,
+                        );
+                        if let Some(hazard) = rolled {
+                            hazards.push(hazard);
+                            placed_hazards += 1;
+                        }
+                    }
+                    last = Some(*column);
+                }
+            }
+            for (x, hazard) in hazards.iter_mut() {
+                *x += hazard.advance(TIME_STEP);
+            }
+            hazards.retain(|(x, hazard)| {
+                *x > -SCREEN.x / 2.0 - PIPE.x && hazard.y() < SCREEN.y / 2.0 + PIPE.x
+            });
+
+            let ahead = columns
+                .iter()
+                .filter(|column| column.x >= exit_x())
+                .min_by(|a, b| a.x.total_cmp(&b.x))
+                .unwrap();
+            let mut frame = Frame {
+                blocked: vec![(f32::NEG_INFINITY, lowest_y())],
+                target: ahead.gap_center_at(ahead.x.min(entry_x())),
+                score,
+            };
+            for column in columns.iter() {
+                let (offset, gap_size) = column.motion.gap_at(column.x);
+                for half in [1.0, -1.0] {
+                    let pipe_y = column.y + offset + pipe_translation_y(half, gap_size);
+                    frame.block(column.x, pipe_y, PIPE);
+                }
+            }
+            for (x, hazard) in hazards.iter() {
+                frame.block(*x, hazard.y(), hazard.kind.hitbox());
+            }
+
+            for (n, column) in columns.iter().enumerate() {
+                if countable[n] && column.x < PLAYER_POS_X {
+                    countable[n] = false;
+                    score += 1;
+                }
+            }
+            frame.score = score;
+            frames.push(frame);
+        }
+        (frames, placed_hazards)
+    }
+
+    // Searches every way of flapping through the course, frame by frame,
+    // for one that makes it to the end. Flaps only count once the fade into
+    // play is over. The search is depth first, trying what a simple bot
+    // holding the bird just under the gap ahead would do first. Returns the
+    // best score any way got to if none makes it.
+    fn play(frames: &[Frame]) -> Option<usize> {
+        let fade_frames = (FADE_SECS / TIME_STEP).round() as usize;
+        let flapping = fall_speed(JUMP_SPEED, SCALED_GRAVITY, TIME_STEP);
+        // frames flown, height and speed
+        let mut stack = vec![(0, 0.0, 0.0)];
+        let mut seen = HashSet::new();
+        let mut furthest = 0;
+
+        while let Some((flown, y, speed)) = stack.pop() {
+            if flown == frames.len() {
+                return None;
+            }
+            furthest = furthest.max(flown);
+
+            let frame = &frames[flown];
+            let falling = fall_speed(speed, SCALED_GRAVITY, TIME_STEP);
+            let target = frame.target.max(lowest_y() + FLAP_BELOW);
+            let bot_flaps = y + falling * TIME_STEP < target - FLAP_BELOW;
+            let mut choices = vec![falling];
+            if flown >= fade_frames {
+                // the last one pushed is tried first
+                if bot_flaps {
+                    choices.push(flapping);
+                } else {
+                    choices.insert(0, flapping);
+                }
+            }
+
+            for speed in choices {
+                let y = (y + speed * TIME_STEP).clamp(PLAYER_MIN_Y, SCREEN.y / 2.0);
+                let state = (flown + 1, speed.to_bits(), (y / SEARCH_CELL).round() as i32);
+                if frame.is_clear(y) && seen.insert(state) {
+                    stack.push((flown + 1, y, speed));
+                }
+            }
+        }
+        Some(frames[furthest].score)
+    }
+
+    fn assert_survivable(auto_moving: &AutoMoving) {
+        let mut placed_hazards = 0;
+        for seed in 0..SEEDS {
+            let (frames, hazards) = course(seed, auto_moving);
+            placed_hazards += hazards;
+            if let Some(score) = play(&frames) {
+                panic!("seed {} can't be survived past a score of {}", seed, score);
+            }
+        }
+        // the courses get far enough for hazards to be part of them
+        assert!(placed_hazards > SEEDS as usize);
+    }
+
+    #[test]
+    fn every_course_is_survivable() {
+        assert_survivable(&auto_moving(PIPE_OPENING_Y_POS_FACTOR));
+    }
+
+    // Wide enough for some random gaps to be out of reach, which the
+    // generator has to repair.
+    #[test]
+    fn wider_courses_are_repaired() {
+        assert_survivable(&auto_moving(PIPE_OPENING_Y_POS_FACTOR * 1.4));
+    }
+
+    #[test]
+    fn unreachable_gaps_are_moved_within_reach() {
+        let previous = PlacedColumn {
+            x: PIPE_START_X,
+            y: 0.0,
+            motion: PipeMotion::default(),
+        };
+        let x = previous.x + SPACE_BETWEEN_PIPES;
+        let motion = PipeMotion::default();
+
+        let too_high = reachable_gap_y(&previous, x, SCREEN.y, &motion);
+        assert!(too_high < SCREEN.y && too_high > 0.0);
+        let too_low = reachable_gap_y(&previous, x, -SCREEN.y, &motion);
+        assert!(too_low > -SCREEN.y && too_low < 0.0);
+        let reachable = PIPE_OPENING_Y_POS_FACTOR / 2.0;
+        assert_eq!(reachable_gap_y(&previous, x, reachable, &motion), reachable);
+    }
+}
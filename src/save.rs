@@ -0,0 +1,234 @@
+use crate::{
+    components::Scoreboard,
+    modes::GameMode,
+    settings::{BirdSkin, Theme},
+    storage,
+    time_attack::TIME_ATTACK_TABLE_SIZE,
+};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub const SAVE_FILE: &str = "save.ron";
+// scores are counted in buckets this wide, the last one takes everything above
+pub const HISTOGRAM_BUCKET: usize = 10;
+pub const HISTOGRAM_BUCKETS: usize = 5;
+
+// Totals over every run ever played in the modes that keep best scores.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LifetimeStats {
+    pub games: usize,
+    // the score is the number of pipes passed
+    pub pipes: usize,
+    pub flaps: usize,
+    pub best_score: usize,
+    // most seconds of play in one sitting
+    pub longest_session: f32,
+    pub histogram: [usize; HISTOGRAM_BUCKETS],
+}
+
+impl LifetimeStats {
+    pub fn average_score(&self) -> f32 {
+        if self.games == 0 {
+            return 0.0;
+        }
+        self.pipes as f32 / self.games as f32
+    }
+
+    pub fn record_run(&mut self, mode: GameMode, score: usize, flaps: usize, session: f32) -> bool {
+        if !mode.records_high_score() {
+            return false;
+        }
+        self.games += 1;
+        self.pipes += score;
+        self.flaps += flaps;
+        self.best_score = self.best_score.max(score);
+        self.longest_session = self.longest_session.max(session);
+        self.histogram[(score / HISTOGRAM_BUCKET).min(HISTOGRAM_BUCKETS - 1)] += 1;
+        true
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AchievementProgress {
+    // ids from `assets/achievements.ron`
+    pub unlocked: Vec<String>,
+    pub first_pipe_deaths: usize,
+}
+
+// Progress that outlives a single run. Written whenever a run ends.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaveData {
+    pub best_scores: HashMap<GameMode, usize>,
+    // best time attack runs, highest first
+    pub time_attack_scores: Vec<usize>,
+    // banked coins
+    pub wallet: usize,
+    // bought skins and themes, free ones are always available
+    pub unlocked_skins: Vec<BirdSkin>,
+    pub unlocked_themes: Vec<Theme>,
+    pub lifetime: LifetimeStats,
+    pub achievements: AchievementProgress,
+}
+
+impl SaveData {
+    pub fn load() -> Self {
+        storage::load(SAVE_FILE).unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        storage::save(SAVE_FILE, self);
+    }
+
+    pub fn best_score(&self, mode: GameMode) -> usize {
+        self.best_scores.get(&mode).copied().unwrap_or(0)
+    }
+
+    // returns true if the score is a new best for the mode, modes that keep
+    // no high score never have one
+    pub fn record_score(&mut self, mode: GameMode, score: usize) -> bool {
+        if !mode.records_high_score() || score <= self.best_score(mode) {
+            return false;
+        }
+        self.best_scores.insert(mode, score);
+        true
+    }
+
+    pub fn is_skin_unlocked(&self, skin: BirdSkin) -> bool {
+        skin.price() == 0 || self.unlocked_skins.contains(&skin)
+    }
+
+    pub fn is_theme_unlocked(&self, theme: Theme) -> bool {
+        theme.price() == 0 || self.unlocked_themes.contains(&theme)
+    }
+
+    // the skin to show for a selection that may not be bought yet
+    pub fn skin_or_default(&self, skin: BirdSkin) -> BirdSkin {
+        if self.is_skin_unlocked(skin) {
+            skin
+        } else {
+            BirdSkin::default()
+        }
+    }
+
+    pub fn theme_or_default(&self, theme: Theme) -> Theme {
+        if self.is_theme_unlocked(theme) {
+            theme
+        } else {
+            Theme::default()
+        }
+    }
+
+    // returns true if the skin was bought
+    pub fn buy_skin(&mut self, skin: BirdSkin) -> bool {
+        if self.is_skin_unlocked(skin) || self.wallet < skin.price() {
+            return false;
+        }
+        self.wallet -= skin.price();
+        self.unlocked_skins.push(skin);
+        true
+    }
+
+    // returns true if the theme was bought
+    pub fn buy_theme(&mut self, theme: Theme) -> bool {
+        if self.is_theme_unlocked(theme) || self.wallet < theme.price() {
+            return false;
+        }
+        self.wallet -= theme.price();
+        self.unlocked_themes.push(theme);
+        true
+    }
+
+    // returns true if the score made it into the time attack table
+    pub fn record_time_attack_score(&mut self, score: usize) -> bool {
+        if score == 0 {
+            return false;
+        }
+        let position = self.time_attack_scores.partition_point(|s| *s >= score);
+        if position >= TIME_ATTACK_TABLE_SIZE {
+            return false;
+        }
+        self.time_attack_scores.insert(position, score);
+        self.time_attack_scores.truncate(TIME_ATTACK_TABLE_SIZE);
+        true
+    }
+}
+
+pub fn record_best_score(
+    mode: Res<GameMode>,
+    scoreboard: Res<Scoreboard>,
+    mut save_data: ResMut<SaveData>,
+) {
+    if save_data.record_score(*mode, scoreboard.score) {
+        save_data.save();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn best_scores_are_kept_per_mode() {
+        let mut save_data = SaveData::default();
+        assert!(save_data.record_score(GameMode::Classic, 12));
+        assert!(!save_data.record_score(GameMode::Classic, 12));
+        assert!(!save_data.record_score(GameMode::Classic, 7));
+        assert!(save_data.record_score(GameMode::DailyChallenge, 3));
+        assert_eq!(save_data.best_score(GameMode::Classic), 12);
+        assert_eq!(save_data.best_score(GameMode::DailyChallenge), 3);
+    }
+
+    #[test]
+    fn practice_and_playtests_stay_out_of_lifetime_stats() {
+        let mut lifetime = LifetimeStats::default();
+        assert!(lifetime.record_run(GameMode::Classic, 12, 30, 60.0));
+        assert!(!lifetime.record_run(GameMode::Practice, 45, 90, 120.0));
+        assert!(!lifetime.record_run(GameMode::Playtest, 45, 90, 120.0));
+        assert_eq!(lifetime.games, 1);
+        assert_eq!(lifetime.best_score, 12);
+        assert_eq!(lifetime.average_score(), 12.0);
+        assert_eq!(lifetime.histogram, [0, 1, 0, 0, 0]);
+    }
+
+    #[test]
+    fn time_attack_table_keeps_the_best_runs_in_order() {
+        let mut save_data = SaveData::default();
+        assert!(!save_data.record_time_attack_score(0));
+        for score in [4, 9, 1, 9, 6, 3] {
+            assert!(save_data.record_time_attack_score(score));
+        }
+        assert_eq!(save_data.time_attack_scores, [9, 9, 6, 4, 3]);
+        assert!(!save_data.record_time_attack_score(2));
+        assert!(save_data.record_time_attack_score(5));
+        assert_eq!(save_data.time_attack_scores, [9, 9, 6, 5, 4]);
+    }
+
+    #[test]
+    fn practice_and_playtests_keep_no_best_score() {
+        let mut save_data = SaveData::default();
+        assert!(!save_data.record_score(GameMode::Practice, 40));
+        assert!(!save_data.record_score(GameMode::Playtest, 40));
+        assert!(save_data.best_scores.is_empty());
+    }
+
+    #[test]
+    fn locked_items_cost_coins() {
+        let mut save_data = SaveData {
+            wallet: BirdSkin::Blue.price() - 1,
+            ..default()
+        };
+        assert!(!save_data.buy_skin(BirdSkin::Blue));
+        save_data.wallet += 1;
+        assert!(save_data.buy_skin(BirdSkin::Blue));
+        assert_eq!(save_data.wallet, 0);
+        assert!(!save_data.buy_skin(BirdSkin::Blue));
+        assert!(save_data.is_theme_unlocked(Theme::Day));
+        // a locked selection shows as the free default
+        assert_eq!(save_data.skin_or_default(BirdSkin::Yellow), BirdSkin::Red);
+        assert_eq!(save_data.theme_or_default(Theme::Night), Theme::Day);
+    }
+}
@@ -0,0 +1,491 @@
+use crate::{
+    components::{Pipe, Player},
+    locale::Language,
+    parallax::{BackgroundLayer, Parallax},
+    save::SaveData,
+    setup::bird_atlas,
+    storage,
+};
+use bevy::{
+    prelude::*,
+    render::{
+        view::{ExtractedWindows, WindowSystem},
+        RenderApp, RenderStage, RenderWorld,
+    },
+    utils::HashMap,
+    window::{PresentMode, WindowId, WindowMode},
+};
+use serde::{Deserialize, Serialize};
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{Duration, Instant};
+
+pub const SETTINGS_FILE: &str = "settings.ron";
+// Bump when the layout of `Settings` changes and add a step to `migrate`.
+pub const SETTINGS_VERSION: u32 = 1;
+pub const VOLUME_STEP: f32 = 0.1;
+pub const FRAME_CAPS: [Option<u32>; 4] = [None, Some(30), Some(60), Some(120)];
+pub const UI_SCALES: [f32; 3] = [1.0, 1.25, 1.5];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DisplayMode {
+    Windowed,
+    Borderless,
+    Fullscreen,
+}
+
+impl DisplayMode {
+    pub fn next(self) -> Self {
+        match self {
+            DisplayMode::Windowed => DisplayMode::Borderless,
+            DisplayMode::Borderless => DisplayMode::Fullscreen,
+            DisplayMode::Fullscreen => DisplayMode::Windowed,
+        }
+    }
+
+    pub fn window_mode(self) -> WindowMode {
+        match self {
+            DisplayMode::Windowed => WindowMode::Windowed,
+            DisplayMode::Borderless => WindowMode::BorderlessFullscreen,
+            DisplayMode::Fullscreen => WindowMode::Fullscreen,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisplaySettings {
+    pub mode: DisplayMode,
+    pub vsync: bool,
+    // frames per second, None means uncapped
+    pub frame_cap: Option<u32>,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        DisplaySettings {
+            mode: DisplayMode::Windowed,
+            vsync: true,
+            frame_cap: None,
+        }
+    }
+}
+
+impl DisplaySettings {
+    pub fn present_mode(&self) -> PresentMode {
+        if self.vsync {
+            PresentMode::Fifo
+        } else {
+            PresentMode::Immediate
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    // 0.0 - 1.0
+    pub volume: f32,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        AudioSettings { volume: 0.8 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BirdSkin {
+    #[default]
+    Red,
+    Blue,
+    Yellow,
+}
+
+impl BirdSkin {
+    pub const ALL: [BirdSkin; 3] = [BirdSkin::Red, BirdSkin::Blue, BirdSkin::Yellow];
+
+    pub fn sprite_sheet(self) -> &'static str {
+        match self {
+            BirdSkin::Red => "sprites/redbird.png",
+            BirdSkin::Blue => "sprites/bluebird.png",
+            BirdSkin::Yellow => "sprites/yellowbird.png",
+        }
+    }
+
+    // in coins, 0 for skins available from the start
+    pub fn price(self) -> usize {
+        match self {
+            BirdSkin::Red => 0,
+            BirdSkin::Blue => 50,
+            BirdSkin::Yellow => 100,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Theme {
+    #[default]
+    Day,
+    Night,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 2] = [Theme::Day, Theme::Night];
+
+    pub fn background(self, layer: BackgroundLayer) -> String {
+        let theme = match self {
+            Theme::Day => "day",
+            Theme::Night => "night",
+        };
+        format!("sprites/background-{}-{}.png", theme, layer.name())
+    }
+
+    pub fn pipe(self) -> &'static str {
+        match self {
+            Theme::Day => "sprites/pipe-green.png",
+            Theme::Night => "sprites/pipe-red.png",
+        }
+    }
+
+    // in coins, 0 for themes available from the start
+    pub fn price(self) -> usize {
+        match self {
+            Theme::Day => 0,
+            Theme::Night => 75,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FlapKey {
+    Space,
+    Up,
+    W,
+}
+
+impl FlapKey {
+    pub const ALL: [FlapKey; 3] = [FlapKey::Space, FlapKey::Up, FlapKey::W];
+
+    pub fn key_code(self) -> KeyCode {
+        match self {
+            FlapKey::Space => KeyCode::Space,
+            FlapKey::Up => KeyCode::Up,
+            FlapKey::W => KeyCode::W,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ControlSettings {
+    pub flap_key: FlapKey,
+}
+
+impl Default for ControlSettings {
+    fn default() -> Self {
+        ControlSettings {
+            flap_key: FlapKey::Space,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccessibilitySettings {
+    // no camera shake or flashes, a still background and a level bird
+    pub reduced_motion: bool,
+    // outlines the pipes and the bird
+    pub high_contrast: bool,
+    // multiplier for the score and menu text, one of `UI_SCALES`
+    pub ui_scale: f32,
+}
+
+impl Default for AccessibilitySettings {
+    fn default() -> Self {
+        AccessibilitySettings {
+            reduced_motion: false,
+            high_contrast: false,
+            ui_scale: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PracticeSettings {
+    // crashes only flash the bird instead of rewinding to the last checkpoint
+    pub invulnerable: bool,
+    // world speed multiplier, 0.25 - 1.0
+    pub game_speed: f32,
+}
+
+impl Default for PracticeSettings {
+    fn default() -> Self {
+        PracticeSettings {
+            invulnerable: false,
+            game_speed: 1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    // files written before versioning was introduced deserialize as 0
+    #[serde(default)]
+    pub version: u32,
+    pub display: DisplaySettings,
+    pub audio: AudioSettings,
+    pub skin: BirdSkin,
+    pub theme: Theme,
+    pub controls: ControlSettings,
+    pub accessibility: AccessibilitySettings,
+    pub practice: PracticeSettings,
+    pub language: Language,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            version: SETTINGS_VERSION,
+            display: default(),
+            audio: default(),
+            skin: BirdSkin::Red,
+            theme: Theme::Day,
+            controls: default(),
+            accessibility: default(),
+            practice: default(),
+            language: default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> Self {
+        storage::load(SETTINGS_FILE)
+            .map(Settings::migrate)
+            .unwrap_or_default()
+    }
+
+    fn migrate(mut self) -> Self {
+        if self.version > SETTINGS_VERSION {
+            warn!(
+                "settings file version {} is newer than {}, using defaults",
+                self.version, SETTINGS_VERSION
+            );
+            return Settings::default();
+        }
+
+        // version 0 only had the display section, the rest is filled in by
+        // serde defaults
+        self.version = SETTINGS_VERSION;
+        self
+    }
+}
+
+// Steps to the neighbouring entry of a fixed list of choices, wrapping around.
+pub fn cycle<T: Copy + PartialEq>(choices: &[T], current: T, forward: bool) -> T {
+    let index = choices.iter().position(|c| *c == current).unwrap_or(0);
+    let len = choices.len();
+    let next = if forward {
+        (index + 1) % len
+    } else {
+        (index + len - 1) % len
+    };
+    choices[next]
+}
+
+pub fn save_settings(settings: Res<Settings>) {
+    if settings.is_changed() && !settings.is_added() {
+        storage::save(SETTINGS_FILE, &*settings);
+    }
+}
+
+pub fn apply_display_settings(settings: Res<Settings>, mut windows: ResMut<Windows>) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    if let Some(window) = windows.get_primary_mut() {
+        let display = &settings.display;
+        if window.mode() != display.mode.window_mode() {
+            window.set_mode(display.mode.window_mode());
+        }
+        if window.present_mode() != display.present_mode() {
+            window.set_present_mode(display.present_mode());
+        }
+    }
+}
+
+pub fn apply_bird_skin(
+    settings: Res<Settings>,
+    save_data: Res<SaveData>,
+    asset_server: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    mut query: Query<&mut Handle<TextureAtlas>, With<Player>>,
+) {
+    if !settings.is_changed() && !save_data.is_changed() {
+        return;
+    }
+
+    let skin = save_data.skin_or_default(settings.skin);
+    let texture_handle = asset_server.load(skin.sprite_sheet());
+    for mut atlas_handle in query.iter_mut() {
+        let unchanged = matches!(
+            texture_atlases.get(atlas_handle.as_ref()),
+            Some(atlas) if atlas.texture == texture_handle
+        );
+        if !unchanged {
+            *atlas_handle = texture_atlases.add(bird_atlas(texture_handle.clone()));
+        }
+    }
+}
+
+pub fn apply_theme(
+    settings: Res<Settings>,
+    save_data: Res<SaveData>,
+    asset_server: Res<AssetServer>,
+    mut background_query: Query<(&Parallax, &mut Handle<Image>), Without<Pipe>>,
+    mut pipe_query: Query<&mut Handle<Image>, (With<Pipe>, Without<Parallax>)>,
+) {
+    if !settings.is_changed() && !save_data.is_changed() {
+        return;
+    }
+
+    let theme = save_data.theme_or_default(settings.theme);
+    for (parallax, mut image) in background_query.iter_mut() {
+        let background = asset_server.load(&theme.background(parallax.layer));
+        if *image != background {
+            *image = background;
+        }
+    }
+
+    let pipe = asset_server.load(theme.pipe());
+    for mut image in pipe_query.iter_mut() {
+        if *image != pipe {
+            *image = pipe.clone();
+        }
+    }
+}
+
+pub fn handle_display_hotkeys(keyboard_input: Res<Input<KeyCode>>, mut settings: ResMut<Settings>) {
+    if keyboard_input.just_pressed(KeyCode::F11) {
+        settings.display.mode = settings.display.mode.next();
+    }
+}
+
+// Sleeps away whatever is left of the frame budget. Browsers already pace
+// frames through requestAnimationFrame so this only exists on desktop.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn frame_limiter(settings: Res<Settings>, mut last_frame: Local<Option<Instant>>) {
+    if let (Some(cap), Some(last)) = (settings.display.frame_cap, *last_frame) {
+        let budget = Duration::from_secs_f64(1.0 / cap.max(1) as f64);
+        if let Some(remaining) = budget.checked_sub(last.elapsed()) {
+            std::thread::sleep(remaining);
+        }
+    }
+
+    *last_frame = Some(Instant::now());
+}
+
+// Bevy only reads a window's present mode when its surface is first created,
+// so changes are carried over to the render world by hand and the surface is
+// reconfigured in place.
+#[derive(Default)]
+struct ExtractedPresentModes(HashMap<WindowId, PresentMode>);
+
+fn extract_present_modes(mut render_world: ResMut<RenderWorld>, windows: Res<Windows>) {
+    let mut modes = render_world.resource_mut::<ExtractedPresentModes>();
+    for window in windows.iter() {
+        modes.0.insert(window.id(), window.present_mode());
+    }
+}
+
+fn reconfigure_present_modes(
+    modes: Res<ExtractedPresentModes>,
+    mut extracted_windows: ResMut<ExtractedWindows>,
+) {
+    for (id, present_mode) in modes.0.iter() {
+        if let Some(window) = extracted_windows.get_mut(id) {
+            if window.present_mode != *present_mode {
+                window.present_mode = *present_mode;
+                window.size_changed = true;
+            }
+        }
+    }
+}
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.world.contains_resource::<Settings>() {
+            app.insert_resource(Settings::load());
+        }
+
+        app.add_system(handle_display_hotkeys)
+            .add_system(apply_display_settings)
+            .add_system(apply_bird_skin)
+            .add_system(apply_theme)
+            .add_system(save_settings);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_system_to_stage(CoreStage::Last, frame_limiter);
+
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .init_resource::<ExtractedPresentModes>()
+                .add_system_to_stage(RenderStage::Extract, extract_present_modes)
+                .add_system_to_stage(
+                    RenderStage::Prepare,
+                    reconfigure_present_modes.before(WindowSystem::Prepare),
+                );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_0_files_keep_their_display_settings() {
+        let raw = "(display: (mode: Borderless, vsync: false, frame_cap: Some(60)))";
+        let settings = ron::from_str::<Settings>(raw).unwrap().migrate();
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.display.mode, DisplayMode::Borderless);
+        assert!(!settings.display.vsync);
+        assert_eq!(settings.display.frame_cap, Some(60));
+        assert_eq!(settings.audio, AudioSettings::default());
+        assert_eq!(settings.accessibility.ui_scale, 1.0);
+    }
+
+    #[test]
+    fn newer_files_fall_back_to_defaults() {
+        let raw = format!("(version: {}, skin: Blue)", SETTINGS_VERSION + 1);
+        let settings = ron::from_str::<Settings>(&raw).unwrap().migrate();
+        assert_eq!(settings, Settings::default());
+    }
+
+    #[test]
+    fn current_files_round_trip() {
+        let settings = Settings {
+            theme: Theme::Night,
+            language: Language::German,
+            ..default()
+        };
+        let raw = ron::to_string(&settings).unwrap();
+        let loaded = ron::from_str::<Settings>(&raw).unwrap().migrate();
+        assert_eq!(loaded, settings);
+    }
+
+    #[test]
+    fn cycle_wraps_around() {
+        assert_eq!(cycle(&FRAME_CAPS, None, false), Some(120));
+        assert_eq!(cycle(&FRAME_CAPS, Some(120), true), None);
+        assert_eq!(cycle(&UI_SCALES, 1.25, true), 1.5);
+        // unknown values start over from the first choice
+        assert_eq!(cycle(&FRAME_CAPS, Some(45), true), Some(30));
+        assert_eq!(cycle(&UI_SCALES, 3.0, true), 1.25);
+    }
+}
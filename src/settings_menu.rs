@@ -0,0 +1,422 @@
+use crate::{
+    components::GameState,
+    locale::{Language, Localization, LocalizedText},
+    menu::*,
+    save::SaveData,
+    settings::{cycle, BirdSkin, FlapKey, Settings, Theme, FRAME_CAPS, UI_SCALES, VOLUME_STEP},
+    time_scale::{clamp_game_speed, GAME_SPEED_STEP},
+};
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct SettingsScreen;
+
+#[derive(Component)]
+pub struct WalletText;
+
+// The settings are split into groups so each fits on screen, even with the
+// UI scaled up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SettingsGroup {
+    #[default]
+    General,
+    Display,
+    Accessibility,
+}
+
+impl SettingsGroup {
+    const ALL: [SettingsGroup; 3] = [
+        SettingsGroup::General,
+        SettingsGroup::Display,
+        SettingsGroup::Accessibility,
+    ];
+
+    fn key(self) -> &'static str {
+        match self {
+            SettingsGroup::General => "settings-group-general",
+            SettingsGroup::Display => "settings-group-display",
+            SettingsGroup::Accessibility => "settings-group-accessibility",
+        }
+    }
+
+    fn items(self) -> &'static [SettingsItem] {
+        match self {
+            SettingsGroup::General => &[
+                SettingsItem::Group(SettingsGroup::General),
+                SettingsItem::Language,
+                SettingsItem::Volume,
+                SettingsItem::Skin,
+                SettingsItem::Theme,
+                SettingsItem::FlapKey,
+                SettingsItem::PracticeInvulnerable,
+                SettingsItem::PracticeSpeed,
+                SettingsItem::Back,
+            ],
+            SettingsGroup::Display => &[
+                SettingsItem::Group(SettingsGroup::Display),
+                SettingsItem::DisplayMode,
+                SettingsItem::Vsync,
+                SettingsItem::FrameCap,
+                SettingsItem::Back,
+            ],
+            SettingsGroup::Accessibility => &[
+                SettingsItem::Group(SettingsGroup::Accessibility),
+                SettingsItem::ReducedMotion,
+                SettingsItem::HighContrast,
+                SettingsItem::UiScale,
+                SettingsItem::Back,
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SettingsItem {
+    // switches to another group
+    Group(SettingsGroup),
+    Language,
+    Volume,
+    Skin,
+    Theme,
+    FlapKey,
+    PracticeInvulnerable,
+    PracticeSpeed,
+    DisplayMode,
+    Vsync,
+    FrameCap,
+    ReducedMotion,
+    HighContrast,
+    UiScale,
+    Back,
+}
+
+fn locked_suffix(localization: &Localization, unlocked: bool, price: usize) -> String {
+    if unlocked {
+        String::new()
+    } else {
+        let price = localization.format("settings-price", &[("price", price.to_string())]);
+        format!(" {}", price)
+    }
+}
+
+fn wallet_label(localization: &Localization, save_data: &SaveData) -> String {
+    localization.format("settings-coins", &[("coins", save_data.wallet.to_string())])
+}
+
+fn on_off(localization: &Localization, value: bool) -> String {
+    localization.get(if value { "settings-on" } else { "settings-off" })
+}
+
+// the message naming a setting's value, e.g. `skin-red` for `BirdSkin::Red`
+fn value_name(localization: &Localization, prefix: &str, value: impl std::fmt::Debug) -> String {
+    localization.get(&format!("{}-{:?}", prefix, value).to_lowercase())
+}
+
+impl SettingsItem {
+    fn label(
+        self,
+        localization: &Localization,
+        settings: &Settings,
+        save_data: &SaveData,
+    ) -> String {
+        let l = localization;
+        match self {
+            SettingsItem::Group(group) => l.get(group.key()),
+            SettingsItem::Language => l.format(
+                "settings-language",
+                &[("language", l.language_name(settings.language))],
+            ),
+            SettingsItem::Volume => l.format(
+                "settings-volume",
+                &[(
+                    "percent",
+                    (settings.audio.volume * 100.0).round().to_string(),
+                )],
+            ),
+            SettingsItem::Skin => l.format(
+                "settings-skin",
+                &[
+                    ("skin", value_name(l, "skin", settings.skin)),
+                    (
+                        "price",
+                        locked_suffix(
+                            l,
+                            save_data.is_skin_unlocked(settings.skin),
+                            settings.skin.price(),
+                        ),
+                    ),
+                ],
+            ),
+            SettingsItem::Theme => l.format(
+                "settings-theme",
+                &[
+                    ("theme", value_name(l, "theme", settings.theme)),
+                    (
+                        "price",
+                        locked_suffix(
+                            l,
+                            save_data.is_theme_unlocked(settings.theme),
+                            settings.theme.price(),
+                        ),
+                    ),
+                ],
+            ),
+            SettingsItem::FlapKey => l.format(
+                "settings-flap-key",
+                &[("key", value_name(l, "key", settings.controls.flap_key))],
+            ),
+            SettingsItem::ReducedMotion => l.format(
+                "settings-reduced-motion",
+                &[("value", on_off(l, settings.accessibility.reduced_motion))],
+            ),
+            SettingsItem::HighContrast => l.format(
+                "settings-high-contrast",
+                &[("value", on_off(l, settings.accessibility.high_contrast))],
+            ),
+            SettingsItem::UiScale => l.format(
+                "settings-ui-scale",
+                &[(
+                    "percent",
+                    (settings.accessibility.ui_scale * 100.0)
+                        .round()
+                        .to_string(),
+                )],
+            ),
+            SettingsItem::PracticeInvulnerable => l.format(
+                "settings-practice-no-crash",
+                &[("value", on_off(l, settings.practice.invulnerable))],
+            ),
+            SettingsItem::PracticeSpeed => l.format(
+                "settings-practice-speed",
+                &[(
+                    "percent",
+                    (settings.practice.game_speed * 100.0).round().to_string(),
+                )],
+            ),
+            SettingsItem::DisplayMode => l.format(
+                "settings-display",
+                &[("mode", value_name(l, "display", settings.display.mode))],
+            ),
+            SettingsItem::Vsync => l.format(
+                "settings-vsync",
+                &[("value", on_off(l, settings.display.vsync))],
+            ),
+            SettingsItem::FrameCap => match settings.display.frame_cap {
+                Some(cap) => l.format("settings-fps-cap", &[("cap", cap.to_string())]),
+                None => l.get("settings-fps-cap-off"),
+            },
+            SettingsItem::Back => l.get("menu-back"),
+        }
+    }
+
+    fn change(self, settings: &mut Settings, forward: bool) {
+        match self {
+            SettingsItem::Language => {
+                settings.language = cycle(&Language::ALL, settings.language, forward);
+            }
+            SettingsItem::Volume => {
+                let step = if forward { VOLUME_STEP } else { -VOLUME_STEP };
+                let volume = ((settings.audio.volume + step) / VOLUME_STEP).round() * VOLUME_STEP;
+                settings.audio.volume = volume.clamp(0.0, 1.0);
+            }
+            SettingsItem::Skin => {
+                settings.skin = cycle(&BirdSkin::ALL, settings.skin, forward);
+            }
+            SettingsItem::Theme => {
+                settings.theme = cycle(&Theme::ALL, settings.theme, forward);
+            }
+            SettingsItem::FlapKey => {
+                let flap_key = &mut settings.controls.flap_key;
+                *flap_key = cycle(&FlapKey::ALL, *flap_key, forward);
+            }
+            SettingsItem::ReducedMotion => {
+                let reduced_motion = &mut settings.accessibility.reduced_motion;
+                *reduced_motion = !*reduced_motion;
+            }
+            SettingsItem::HighContrast => {
+                let high_contrast = &mut settings.accessibility.high_contrast;
+                *high_contrast = !*high_contrast;
+            }
+            SettingsItem::UiScale => {
+                let ui_scale = &mut settings.accessibility.ui_scale;
+                *ui_scale = cycle(&UI_SCALES, *ui_scale, forward);
+            }
+            SettingsItem::PracticeInvulnerable => {
+                settings.practice.invulnerable = !settings.practice.invulnerable;
+            }
+            SettingsItem::PracticeSpeed => {
+                let step = if forward {
+                    GAME_SPEED_STEP
+                } else {
+                    -GAME_SPEED_STEP
+                };
+                settings.practice.game_speed =
+                    clamp_game_speed(settings.practice.game_speed + step);
+            }
+            SettingsItem::DisplayMode => {
+                settings.display.mode = if forward {
+                    settings.display.mode.next()
+                } else {
+                    settings.display.mode.next().next()
+                };
+            }
+            SettingsItem::Vsync => settings.display.vsync = !settings.display.vsync,
+            SettingsItem::FrameCap => {
+                settings.display.frame_cap =
+                    cycle(&FRAME_CAPS, settings.display.frame_cap, forward);
+            }
+            SettingsItem::Group(_) | SettingsItem::Back => {}
+        }
+    }
+}
+
+fn spawn_settings_screen(
+    commands: &mut Commands,
+    font: Handle<Font>,
+    localization: &Localization,
+    settings: &Settings,
+    save_data: &SaveData,
+    group: SettingsGroup,
+) {
+    commands
+        .spawn_bundle(menu_root())
+        .insert(SettingsScreen)
+        .with_children(|parent| {
+            spawn_menu_title(parent, font.clone(), &localization.get("settings-title"))
+                .insert(LocalizedText("settings-title"));
+            let wallet = wallet_label(localization, save_data);
+            spawn_menu_text(parent, font.clone(), &wallet).insert(WalletText);
+            for (index, item) in group.items().iter().enumerate() {
+                let label = item.label(localization, settings, save_data);
+                spawn_menu_item(parent, font.clone(), index, &label);
+            }
+        });
+}
+
+pub fn setup_settings_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    localization: Res<Localization>,
+    settings: Res<Settings>,
+    save_data: Res<SaveData>,
+    group: Res<SettingsGroup>,
+) {
+    let font = localization.font(&asset_server);
+    spawn_settings_screen(
+        &mut commands,
+        font,
+        &localization,
+        &settings,
+        &save_data,
+        *group,
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn handle_settings_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    localization: Res<Localization>,
+    mut group: ResMut<SettingsGroup>,
+    mut game_state: ResMut<State<GameState>>,
+    mut settings: ResMut<Settings>,
+    mut save_data: ResMut<SaveData>,
+    mut menu_events: EventReader<MenuEvent>,
+    screen_query: Query<Entity, With<SettingsScreen>>,
+) {
+    let items = group.items();
+    for event in menu_events.iter() {
+        let item = items[event.item.min(items.len() - 1)];
+        match (item, event.action) {
+            (_, MenuAction::Back) | (SettingsItem::Back, MenuAction::Activate) => {
+                game_state.set(GameState::MainMenu).unwrap();
+                return;
+            }
+            (SettingsItem::Back, _) => {}
+            (SettingsItem::Group(current), action) => {
+                let forward = action != MenuAction::Previous;
+                *group = cycle(&SettingsGroup::ALL, current, forward);
+                for entity in screen_query.iter() {
+                    commands.entity(entity).despawn_recursive();
+                }
+                let font = localization.font(&asset_server);
+                spawn_settings_screen(
+                    &mut commands,
+                    font,
+                    &localization,
+                    &settings,
+                    &save_data,
+                    *group,
+                );
+                return;
+            }
+            // activating a locked skin or theme buys it
+            (SettingsItem::Skin, MenuAction::Activate)
+                if !save_data.is_skin_unlocked(settings.skin) =>
+            {
+                if save_data.buy_skin(settings.skin) {
+                    save_data.save();
+                }
+            }
+            (SettingsItem::Theme, MenuAction::Activate)
+                if !save_data.is_theme_unlocked(settings.theme) =>
+            {
+                if save_data.buy_theme(settings.theme) {
+                    save_data.save();
+                }
+            }
+            (_, MenuAction::Previous) => item.change(&mut settings, false),
+            (_, MenuAction::Next | MenuAction::Activate) => item.change(&mut settings, true),
+        }
+    }
+}
+
+pub fn update_settings_labels(
+    localization: Res<Localization>,
+    group: Res<SettingsGroup>,
+    settings: Res<Settings>,
+    save_data: Res<SaveData>,
+    item_query: Query<(&MenuItem, &Children)>,
+    mut text_query: Query<&mut Text, (With<MenuItemText>, Without<WalletText>)>,
+    mut wallet_query: Query<&mut Text, With<WalletText>>,
+) {
+    if !settings.is_changed() && !save_data.is_changed() && !localization.is_changed() {
+        return;
+    }
+
+    for mut text in wallet_query.iter_mut() {
+        text.sections[0].value = wallet_label(&localization, &save_data);
+    }
+
+    for (item, children) in item_query.iter() {
+        let label = match group.items().get(item.0) {
+            Some(setting) => setting.label(&localization, &settings, &save_data),
+            None => continue,
+        };
+        for child in children.iter() {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].value = label.clone();
+            }
+        }
+    }
+}
+
+pub struct SettingsMenuPlugin;
+
+impl Plugin for SettingsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SettingsGroup>()
+            .add_system_set(
+                SystemSet::on_enter(GameState::Settings).with_system(setup_settings_screen),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::Settings)
+                    .with_system(handle_settings_menu)
+                    .with_system(update_settings_labels),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::Settings)
+                    .with_system(despawn_screen::<SettingsScreen>),
+            );
+    }
+}
@@ -1,23 +1,67 @@
-use crate::components::*;
+use crate::{
+    accessibility::ScaledText,
+    camera_effects::GameCamera,
+    components::*,
+    levels::{place_level_column, CurrentLevel, Level, LEVEL_START_X},
+    locale::{Localization, LocalizedText},
+    modes::{CourseRng, CourseSeed, GameMode},
+    pipe_behaviors::{pipe_translation_y, PipeHalf, PipeMotion},
+    save::SaveData,
+    settings::Settings,
+};
 use bevy::{
-    ecs::query::QueryIter,
     math::{vec2, vec3},
     prelude::*,
     utils::Duration,
 };
 use rand::prelude::*;
 
+pub const SCORE_FONT_SIZE: f32 = 100.0;
+pub const BANNER_FONT_SIZE: f32 = 64.0;
+pub const HINT_FONT_SIZE: f32 = 32.0;
+
+// A line of localized text in the middle of the screen, hidden until its
+// screen comes up.
+fn spawn_banner(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    localization: &Localization,
+    key: &'static str,
+    font_size: f32,
+    y: f32,
+) -> Entity {
+    let style = TextStyle {
+        font: localization.font(asset_server),
+        font_size,
+        color: Color::WHITE,
+    };
+    let alignment = TextAlignment {
+        vertical: VerticalAlign::Center,
+        horizontal: HorizontalAlign::Center,
+    };
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(localization.get(key), style, alignment),
+            transform: Transform::from_xyz(0.0, y, 11.0),
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(LocalizedText(key))
+        .insert(ScaledText(font_size))
+        .id()
+}
+
 pub fn setup_font(mut commands: Commands, asset_server: Res<AssetServer>) {
     let shadow_font = asset_server.load("flappy-font.ttf");
     let font = shadow_font.clone();
     let shadow_style = TextStyle {
         font: shadow_font,
-        font_size: 100.0,
+        font_size: SCORE_FONT_SIZE,
         color: Color::BLACK,
     };
     let style = TextStyle {
         font,
-        font_size: 100.0,
+        font_size: SCORE_FONT_SIZE,
         color: Color::WHITE,
     };
     let alignment = TextAlignment {
@@ -32,36 +76,72 @@ pub fn setup_font(mut commands: Commands, asset_server: Res<AssetServer>) {
             transform: Transform::from_xyz(5.0, SCREEN.y / 4.0 - 5.0, 10.0),
             ..default()
         })
-        .insert(ScoreText);
+        .insert(ScoreText)
+        .insert(ScaledText(SCORE_FONT_SIZE));
     commands
         .spawn_bundle(Text2dBundle {
             text: Text::with_section("0", style, alignment),
             transform: Transform::from_xyz(0.0, SCREEN.y / 4.0, 11.0),
             ..default()
         })
-        .insert(ScoreText);
+        .insert(ScoreText)
+        .insert(ScaledText(SCORE_FONT_SIZE));
 }
 
-pub fn setup_game_over_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let image = asset_server.load("sprites/gameover.png");
-
+pub fn setup_game_over_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    localization: Res<Localization>,
+) {
     commands.spawn().insert(GameOverUIInputTimer(Timer::new(
         Duration::from_millis(500),
         false,
     )));
 
-    commands
-        .spawn_bundle(SpriteBundle {
-            texture: image,
-            transform: Transform {
-                translation: vec3(0.0, 0.0, 10.0),
-                scale: vec3(2.0, 2.0, 1.0),
-                ..default()
-            },
-            visibility: Visibility { is_visible: false },
-            ..default()
-        })
-        .insert(GameOverUI);
+    let banner = spawn_banner(
+        &mut commands,
+        &asset_server,
+        &localization,
+        "game-over",
+        BANNER_FONT_SIZE,
+        0.0,
+    );
+    commands.entity(banner).insert(GameOverUI);
+}
+
+// "Get ready" above the bird and how to start below it.
+pub fn setup_ready_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    localization: Res<Localization>,
+) {
+    let lines = [
+        ("ready-title", BANNER_FONT_SIZE, SCREEN.y / 8.0),
+        ("ready-hint", HINT_FONT_SIZE, -SCREEN.y / 8.0),
+    ];
+    for (key, font_size, y) in lines {
+        let line = spawn_banner(
+            &mut commands,
+            &asset_server,
+            &localization,
+            key,
+            font_size,
+            y,
+        );
+        commands.entity(line).insert(ReadyUI);
+    }
+}
+
+pub fn set_ready_ui_visible(mut query: Query<&mut Visibility, With<ReadyUI>>) {
+    for mut visibility in query.iter_mut() {
+        visibility.is_visible = true;
+    }
+}
+
+pub fn set_ready_ui_hidden(mut query: Query<&mut Visibility, With<ReadyUI>>) {
+    for mut visibility in query.iter_mut() {
+        visibility.is_visible = false;
+    }
 }
 
 pub fn set_game_over_ui_visible(
@@ -82,25 +162,35 @@ pub fn set_game_over_ui_hidden(mut query: Query<(&GameOverUI, &mut Visibility)>)
     }
 }
 
-pub fn setup_pipes(mut commands: Commands, asset_server: Res<AssetServer>) {
-    let pipe_handle = asset_server.load("sprites/pipe-green.png");
+// Where the n-th pipe column starts a run. The first gap is always centered.
+pub fn pipe_start_translation(n: usize, rng: &mut impl Rng) -> Vec3 {
+    let rand_num = if rng.gen_ratio(1, 2) {
+        rng.gen_range(0.5..1.0)
+    } else {
+        rng.gen_range(-1.0..-0.5)
+    };
+    vec3(
+        PIPE_START_X + n as f32 * SPACE_BETWEEN_PIPES,
+        n as f32 * (PIPE_OPENING_Y_POS_FACTOR * rand_num),
+        1.0,
+    )
+}
+
+pub fn setup_pipes(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    save_data: Res<SaveData>,
+) {
+    let theme = save_data.theme_or_default(settings.theme);
+    let pipe_handle = asset_server.load(theme.pipe());
     let mut rng = thread_rng();
 
     for n in 0..2 {
-        let rand_num = if rng.gen_ratio(1, 2) {
-            rng.gen_range(0.5..1.0)
-        } else {
-            rng.gen_range(-1.0..-0.5)
-        };
-
         let parent = commands
             .spawn_bundle(TransformBundle {
                 local: Transform {
-                    translation: vec3(
-                        PIPE_START_X + n as f32 * SPACE_BETWEEN_PIPES,
-                        n as f32 * (PIPE_OPENING_Y_POS_FACTOR * rand_num),
-                        1.0,
-                    ),
+                    translation: pipe_start_translation(n, &mut rng),
                     ..Default::default()
                 },
                 ..default()
@@ -112,6 +202,7 @@ pub fn setup_pipes(mut commands: Commands, asset_server: Res<AssetServer>) {
                 initial: vec3(0.0, 0.0, 0.0),
             })
             .insert(Countable(true))
+            .insert(PipeMotion::default())
             .id();
         let pipe_top = pipe_handle.clone();
         let pipe_bottom = pipe_top.clone();
@@ -122,13 +213,19 @@ pub fn setup_pipes(mut commands: Commands, asset_server: Res<AssetServer>) {
             })
             .insert_bundle(TransformBundle {
                 local: Transform {
-                    translation: vec3(0.0, PIPE.y / 2.0 + VERTICAL_SPACE_BETWEEN_PIPES / 2.0, 0.0),
+                    translation: vec3(
+                        0.0,
+                        pipe_translation_y(1.0, VERTICAL_SPACE_BETWEEN_PIPES),
+                        0.0,
+                    ),
                     scale: vec3(2.0, 2.0, 1.0),
-                    rotation: Quat::from_rotation_z((180.0 as f32).to_radians()),
+                    rotation: Quat::from_rotation_z(180.0_f32.to_radians()),
                 },
                 ..default()
             })
             .insert(Blocker(PIPE))
+            .insert(Pipe)
+            .insert(PipeHalf(1.0))
             .id();
 
         let child_bottom = commands
@@ -140,7 +237,7 @@ pub fn setup_pipes(mut commands: Commands, asset_server: Res<AssetServer>) {
                 local: Transform {
                     translation: vec3(
                         0.0,
-                        -(PIPE.y / 2.0) - (VERTICAL_SPACE_BETWEEN_PIPES / 2.0),
+                        pipe_translation_y(-1.0, VERTICAL_SPACE_BETWEEN_PIPES),
                         0.0,
                     ),
                     scale: vec3(2.0, 2.0, 1.0),
@@ -149,6 +246,8 @@ pub fn setup_pipes(mut commands: Commands, asset_server: Res<AssetServer>) {
                 ..default()
             })
             .insert(Blocker(PIPE))
+            .insert(Pipe)
+            .insert(PipeHalf(-1.0))
             .id();
 
         commands
@@ -180,32 +279,31 @@ pub fn setup_floor(mut commands: Commands, asset_server: Res<AssetServer>) {
             })
             .insert(SpeedAnimated { width: FLOOR.x })
             // compensating for collision not taking rotation into account?
-            .insert(Blocker(FLOOR * 1.2));
+            .insert(Blocker(FLOOR * 1.2))
+            .insert(Floor);
     }
 }
 
+pub fn bird_atlas(texture_handle: Handle<Image>) -> TextureAtlas {
+    TextureAtlas::from_grid(texture_handle, vec2(34.0, 24.0), 3, 1)
+}
+
 pub fn setup_player(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    settings: Res<Settings>,
+    save_data: Res<SaveData>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
 ) {
     // Cameras
-    commands.spawn_bundle(OrthographicCameraBundle::new_2d());
+    commands
+        .spawn_bundle(OrthographicCameraBundle::new_2d())
+        .insert(GameCamera);
     commands.spawn_bundle(UiCameraBundle::default());
 
-    let bg_image = asset_server.load("sprites/background-day.png");
-    commands.spawn_bundle(SpriteBundle {
-        texture: bg_image,
-        transform: Transform {
-            scale: PLAYER_SCALE,
-            ..default()
-        },
-        ..default()
-    });
-
-    let texture_handle = asset_server.load("sprites/redbird.png");
-    let texture_atlas = TextureAtlas::from_grid(texture_handle, vec2(34.0, 24.0), 3, 1);
-    let texture_atlas_handle = texture_atlases.add(texture_atlas);
+    let skin = save_data.skin_or_default(settings.skin);
+    let texture_handle = asset_server.load(skin.sprite_sheet());
+    let texture_atlas_handle = texture_atlases.add(bird_atlas(texture_handle));
     let bird_xy = vec3(PLAYER_POS_X, 0.0, 2.0);
     commands
         .spawn()
@@ -229,34 +327,74 @@ pub fn setup_player(
 pub fn game_over_cleanup(
     _: EventReader<ResetGameEvent>,
     mut scoreboard: ResMut<Scoreboard>,
-    mut player_query: Query<(&Player, &mut Transform)>,
-    mut pipe_query: Query<(&mut Countable, &mut Transform, Without<Player>)>,
     mut text_query: Query<(&ScoreText, &mut Text)>,
 ) {
     scoreboard.score = 0;
     for (_, mut text) in text_query.iter_mut() {
         text.sections.get_mut(0).unwrap().value = "0".to_string();
     }
+}
 
-    let mut rng = thread_rng();
-    let mut n = 0;
-    for (mut countable, mut pipe_transform, _) in pipe_query.iter_mut() {
-        let rand_num = if rng.gen_ratio(1, 2) {
-            rng.gen_range(0.5..1.0)
-        } else {
-            rng.gen_range(-1.0..-0.5)
-        };
-        pipe_transform.translation = vec3(
-            PIPE_START_X + n as f32 * SPACE_BETWEEN_PIPES,
-            n as f32 * (PIPE_OPENING_Y_POS_FACTOR * rand_num),
-            1.0,
-        );
+// Lays the course out for a new run of the selected mode. Runs on entering
+// the ready screen so seeded modes always start from the same layout.
+#[allow(clippy::too_many_arguments)]
+pub fn reset_course(
+    mut commands: Commands,
+    mode: Res<GameMode>,
+    seed: Res<CourseSeed>,
+    levels: Res<Assets<Level>>,
+    mut current_level: ResMut<CurrentLevel>,
+    mut course_rng: ResMut<CourseRng>,
+    mut column_events: EventWriter<ColumnPlacedEvent>,
+    mut player_query: Query<(Entity, &mut Player, &mut Transform, &mut Visibility)>,
+    mut pipe_query: Query<
+        (Entity, &mut Countable, &mut PipeMotion, &mut Transform),
+        Without<Player>,
+    >,
+) {
+    *course_rng = CourseRng::for_seed(&seed);
+    current_level.next = 0;
+    current_level.random_course = mode.plays_level() && current_level.get(*mode, &levels).is_none();
+    if current_level.random_course {
+        warn!("level is not loaded, playing a random course");
+    }
+
+    let mut last_x = LEVEL_START_X;
+    for (n, (entity, mut countable, mut motion, mut pipe_transform)) in
+        pipe_query.iter_mut().enumerate()
+    {
         countable.0 = true;
+        if current_level.plays(*mode) {
+            let column = current_level.next_column(*mode, &levels);
+            place_level_column(
+                &mut column_events,
+                entity,
+                column,
+                last_x,
+                &mut pipe_transform,
+                &mut motion,
+            );
+            last_x = last_x.max(pipe_transform.translation.x);
+            continue;
+        }
 
-        n += 1;
+        pipe_transform.translation = pipe_start_translation(n, &mut course_rng.0);
+        motion.assign(&mut course_rng.0, 0, pipe_transform.translation.x);
+        column_events.send(ColumnPlacedEvent {
+            column: entity,
+            x: pipe_transform.translation.x,
+            y: pipe_transform.translation.y,
+            spacing: SPACE_BETWEEN_PIPES,
+            coin: true,
+            power_up: None,
+        });
     }
 
-    let (_, mut player_transform) = player_query.single_mut();
+    let (entity, mut player, mut player_transform, mut visibility) = player_query.single_mut();
+    commands.entity(entity).remove::<Invulnerable>();
+    visibility.is_visible = true;
+    player.movement_speed = 0.0;
+    player.angle = 0.0;
     player_transform.translation = vec3(PLAYER_POS_X, 0.0, 2.0);
     player_transform.rotation = Quat::from_rotation_z(f32::to_radians(0.0));
 }
@@ -0,0 +1,105 @@
+use crate::{components::*, settings::Settings, transitions::TransitionEvent};
+use bevy::prelude::*;
+
+// practice lets the bird scrape along a pipe, one hit sound per crash will do
+pub const HIT_SOUND_SECS: f32 = 0.5;
+
+// The sound effects in `assets/audio/`, loaded once.
+pub struct Sounds {
+    pub wing: Handle<AudioSource>,
+    pub point: Handle<AudioSource>,
+    pub hit: Handle<AudioSource>,
+    pub die: Handle<AudioSource>,
+    pub swoosh: Handle<AudioSource>,
+}
+
+impl FromWorld for Sounds {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.get_resource::<AssetServer>().unwrap();
+        Sounds {
+            wing: asset_server.load("audio/wing.ogg"),
+            point: asset_server.load("audio/point.ogg"),
+            hit: asset_server.load("audio/hit.ogg"),
+            die: asset_server.load("audio/die.ogg"),
+            swoosh: asset_server.load("audio/swoosh.ogg"),
+        }
+    }
+}
+
+// Plays a sound once at the volume from the settings.
+pub fn play_sound(audio: &Audio, settings: &Settings, sound: &Handle<AudioSource>) {
+    let volume = settings.audio.volume;
+    if volume > 0.0 {
+        audio.play_with_settings(sound.clone(), PlaybackSettings::ONCE.with_volume(volume));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn play_game_sounds(
+    time: Res<Time>,
+    audio: Res<Audio>,
+    settings: Res<Settings>,
+    sounds: Res<Sounds>,
+    mut hit_cooldown: Local<f32>,
+    mut flap_events: EventReader<FlapEvent>,
+    mut scored_events: EventReader<PointScoredEvent>,
+    mut collision_events: EventReader<CollisionEvent>,
+) {
+    *hit_cooldown = (*hit_cooldown - time.delta_seconds()).max(0.0);
+    if flap_events.iter().count() > 0 {
+        play_sound(&audio, &settings, &sounds.wing);
+    }
+    if scored_events.iter().count() > 0 {
+        play_sound(&audio, &settings, &sounds.point);
+    }
+    if collision_events.iter().count() > 0 && *hit_cooldown == 0.0 {
+        *hit_cooldown = HIT_SOUND_SECS;
+        play_sound(&audio, &settings, &sounds.hit);
+    }
+}
+
+pub fn play_die_sound(audio: Res<Audio>, settings: Res<Settings>, sounds: Res<Sounds>) {
+    play_sound(&audio, &settings, &sounds.die);
+}
+
+// A swoosh whenever the screen changes. The crash into the dying phase
+// already has the hit sound.
+pub fn play_transition_sound(
+    audio: Res<Audio>,
+    settings: Res<Settings>,
+    sounds: Res<Sounds>,
+    mut transition_events: EventReader<TransitionEvent>,
+) {
+    let swoosh = transition_events
+        .iter()
+        .any(|event| event.to != GameState::Dying);
+    if swoosh {
+        play_sound(&audio, &settings, &sounds.swoosh);
+    }
+}
+
+// A flap at the new volume, so changing it in the settings can be heard.
+pub fn preview_volume(
+    audio: Res<Audio>,
+    settings: Res<Settings>,
+    sounds: Res<Sounds>,
+    mut volume: Local<Option<f32>>,
+) {
+    let current = settings.audio.volume;
+    if matches!(*volume, Some(previous) if previous != current) {
+        play_sound(&audio, &settings, &sounds.wing);
+    }
+    *volume = Some(current);
+}
+
+pub struct SoundsPlugin;
+
+impl Plugin for SoundsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Sounds>()
+            .add_system(play_game_sounds)
+            .add_system(play_transition_sound)
+            .add_system(preview_volume)
+            .add_system_set(SystemSet::on_enter(GameState::Dying).with_system(play_die_sound));
+    }
+}
@@ -0,0 +1,247 @@
+use crate::{
+    components::{GameState, Scoreboard},
+    locale::Localization,
+    menu::*,
+    modes::GameMode,
+    save::{SaveData, HISTOGRAM_BUCKET, HISTOGRAM_BUCKETS},
+    telemetry::{track_run, RunTelemetry},
+};
+use bevy::prelude::*;
+
+const HISTOGRAM_BAR_WIDTH: f32 = 240.0;
+const HISTOGRAM_BAR_HEIGHT: f32 = 24.0;
+const HISTOGRAM_LABEL_WIDTH: f32 = 120.0;
+const HISTOGRAM_BAR_COLOR: Color = Color::rgb(0.87, 0.45, 0.11);
+
+#[derive(Component)]
+pub struct StatsScreen;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatsPage {
+    #[default]
+    Bests,
+    Lifetime,
+}
+
+impl StatsPage {
+    fn key(self) -> &'static str {
+        match self {
+            StatsPage::Bests => "stats-page-bests",
+            StatsPage::Lifetime => "stats-page-lifetime",
+        }
+    }
+
+    fn other(self) -> Self {
+        match self {
+            StatsPage::Bests => StatsPage::Lifetime,
+            StatsPage::Lifetime => StatsPage::Bests,
+        }
+    }
+}
+
+fn format_duration(secs: f32) -> String {
+    let secs = secs as usize;
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+fn histogram_label(bucket: usize) -> String {
+    let low = bucket * HISTOGRAM_BUCKET;
+    if bucket == HISTOGRAM_BUCKETS - 1 {
+        format!("{}+", low)
+    } else {
+        format!("{}-{}", low, low + HISTOGRAM_BUCKET - 1)
+    }
+}
+
+fn spawn_histogram(parent: &mut ChildBuilder, font: Handle<Font>, histogram: &[usize]) {
+    let most = histogram.iter().copied().max().unwrap_or(0).max(1);
+    for (bucket, count) in histogram.iter().enumerate() {
+        parent
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    margin: Rect::all(MENU_ITEM_MARGIN),
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                color: UiColor(Color::NONE),
+                ..default()
+            })
+            .with_children(|row| {
+                row.spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(HISTOGRAM_LABEL_WIDTH), Val::Auto),
+                        ..default()
+                    },
+                    color: UiColor(Color::NONE),
+                    ..default()
+                })
+                .with_children(|label| {
+                    spawn_menu_text(label, font.clone(), &histogram_label(bucket));
+                });
+                row.spawn_bundle(NodeBundle {
+                    style: Style {
+                        size: Size::new(
+                            Val::Px(HISTOGRAM_BAR_WIDTH * *count as f32 / most as f32),
+                            Val::Px(HISTOGRAM_BAR_HEIGHT),
+                        ),
+                        ..default()
+                    },
+                    color: UiColor(HISTOGRAM_BAR_COLOR),
+                    ..default()
+                });
+                spawn_menu_text(row, font.clone(), &count.to_string());
+            });
+    }
+}
+
+fn spawn_stats_screen(
+    commands: &mut Commands,
+    font: Handle<Font>,
+    localization: &Localization,
+    save_data: &SaveData,
+    page: StatsPage,
+) {
+    commands
+        .spawn_bundle(menu_root())
+        .insert(StatsScreen)
+        .with_children(|parent| {
+            spawn_menu_title(parent, font.clone(), &localization.get("stats-title"));
+            spawn_menu_item(parent, font.clone(), 0, &localization.get(page.key()));
+            match page {
+                StatsPage::Bests => {
+                    for mode in GameMode::ALL.into_iter().filter(|m| m.records_high_score()) {
+                        let best = localization.format(
+                            "stats-best",
+                            &[
+                                ("mode", localization.get(mode.key())),
+                                ("score", save_data.best_score(mode).to_string()),
+                            ],
+                        );
+                        spawn_menu_text(parent, font.clone(), &best);
+                    }
+                    for (rank, score) in save_data.time_attack_scores.iter().enumerate() {
+                        let entry = localization.format(
+                            "stats-time-attack",
+                            &[
+                                ("rank", (rank + 1).to_string()),
+                                ("score", score.to_string()),
+                            ],
+                        );
+                        spawn_menu_text(parent, font.clone(), &entry);
+                    }
+                }
+                StatsPage::Lifetime => {
+                    let lifetime = &save_data.lifetime;
+                    let lines = [
+                        localization
+                            .format("stats-games", &[("count", lifetime.games.to_string())]),
+                        localization
+                            .format("stats-pipes", &[("count", lifetime.pipes.to_string())]),
+                        localization
+                            .format("stats-flaps", &[("count", lifetime.flaps.to_string())]),
+                        localization.format(
+                            "stats-best-average",
+                            &[
+                                ("best", lifetime.best_score.to_string()),
+                                ("average", format!("{:.1}", lifetime.average_score())),
+                            ],
+                        ),
+                        localization.format(
+                            "stats-longest-session",
+                            &[("time", format_duration(lifetime.longest_session))],
+                        ),
+                    ];
+                    for line in lines {
+                        spawn_menu_text(parent, font.clone(), &line);
+                    }
+                    spawn_histogram(parent, font.clone(), &lifetime.histogram);
+                }
+            }
+            spawn_menu_item(parent, font.clone(), 1, &localization.get("menu-back"));
+        });
+}
+
+pub fn setup_stats_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    localization: Res<Localization>,
+    save_data: Res<SaveData>,
+    page: Res<StatsPage>,
+) {
+    let font = localization.font(&asset_server);
+    spawn_stats_screen(&mut commands, font, &localization, &save_data, *page);
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn handle_stats_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    localization: Res<Localization>,
+    save_data: Res<SaveData>,
+    mut page: ResMut<StatsPage>,
+    mut game_state: ResMut<State<GameState>>,
+    mut menu_events: EventReader<MenuEvent>,
+    screen_query: Query<Entity, With<StatsScreen>>,
+) {
+    for event in menu_events.iter() {
+        match (event.item, event.action) {
+            (_, MenuAction::Back) | (1, MenuAction::Activate) => {
+                game_state.set(GameState::MainMenu).unwrap();
+                return;
+            }
+            (0, MenuAction::Activate | MenuAction::Previous | MenuAction::Next) => {
+                *page = page.other();
+                for entity in screen_query.iter() {
+                    commands.entity(entity).despawn_recursive();
+                }
+                let font = localization.font(&asset_server);
+                spawn_stats_screen(&mut commands, font, &localization, &save_data, *page);
+                return;
+            }
+            _ => {}
+        }
+    }
+}
+
+// Adds a finished run to the lifetime stats, from the record telemetry kept
+// of it. The session is the play time since the game was started, practice
+// and play-tests included even though their runs aren't recorded.
+pub fn record_lifetime_stats(
+    game_state: Res<State<GameState>>,
+    scoreboard: Res<Scoreboard>,
+    telemetry: Res<RunTelemetry>,
+    mut session: Local<f32>,
+    mut save_data: ResMut<SaveData>,
+) {
+    let ended = matches!(
+        game_state.current(),
+        GameState::GameOver | GameState::LevelComplete
+    );
+    let record = match &telemetry.0 {
+        Some(record) if ended => record,
+        _ => return,
+    };
+
+    *session += record.duration;
+    let lifetime = &mut save_data.lifetime;
+    if lifetime.record_run(record.mode, scoreboard.score, record.flaps, *session) {
+        save_data.save();
+    }
+}
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<StatsPage>()
+            .add_system_set(SystemSet::on_enter(GameState::Stats).with_system(setup_stats_screen))
+            .add_system_set(SystemSet::on_update(GameState::Stats).with_system(handle_stats_menu))
+            .add_system_set(
+                SystemSet::on_exit(GameState::Stats).with_system(despawn_screen::<StatsScreen>),
+            )
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                record_lifetime_stats.before(track_run),
+            );
+    }
+}
@@ -0,0 +1,91 @@
+use bevy::log::warn;
+use serde::{de::DeserializeOwned, Serialize};
+
+// Small RON documents (settings, save data) are kept in a `save` directory
+// next to the working directory on desktop and in local storage on the web.
+#[cfg(not(target_arch = "wasm32"))]
+const SAVE_DIR: &str = "save";
+
+pub fn load<T: DeserializeOwned>(name: &str) -> Option<T> {
+    let raw = read(name)?;
+    match ron::from_str(&raw) {
+        Ok(value) => Some(value),
+        Err(err) => {
+            warn!("could not parse {}: {}", name, err);
+            None
+        }
+    }
+}
+
+pub fn save<T: Serialize>(name: &str, value: &T) {
+    let pretty = ron::ser::PrettyConfig::default();
+    match ron::ser::to_string_pretty(value, pretty) {
+        Ok(raw) => write(name, &raw),
+        Err(err) => warn!("could not serialize {}: {}", name, err),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read(name: &str) -> Option<String> {
+    std::fs::read_to_string(std::path::Path::new(SAVE_DIR).join(name)).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write(name: &str, raw: &str) {
+    let dir = std::path::Path::new(SAVE_DIR);
+    if let Err(err) = std::fs::create_dir_all(dir).and_then(|_| std::fs::write(dir.join(name), raw))
+    {
+        warn!("could not write {}: {}", name, err);
+    }
+}
+
+// Adds a line to a log kept next to the save data.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn append_line(name: &str, line: &str) {
+    use std::io::Write;
+
+    let dir = std::path::Path::new(SAVE_DIR);
+    let appended = std::fs::create_dir_all(dir).and_then(|_| {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(name))?;
+        writeln!(file, "{}", line)
+    });
+    if let Err(err) = appended {
+        warn!("could not append to {}: {}", name, err);
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read(name: &str) -> Option<String> {
+    local_storage()?.get_item(name).ok()?
+}
+
+// Local storage has no append, the log is read and written back whole. A log
+// that can't be read is left alone rather than replaced by the new line.
+#[cfg(target_arch = "wasm32")]
+pub fn append_line(name: &str, line: &str) {
+    let storage = match local_storage() {
+        Some(storage) => storage,
+        None => return,
+    };
+    match storage.get_item(name) {
+        Ok(raw) => write(name, &format!("{}{}\n", raw.unwrap_or_default(), line)),
+        Err(_) => warn!("could not read {} from local storage", name),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write(name: &str, raw: &str) {
+    if let Some(storage) = local_storage() {
+        if storage.set_item(name, raw).is_err() {
+            warn!("could not write {} to local storage", name);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{
+    components::*,
+    modes::{CourseSeed, GameMode},
+    pipe_behaviors::PipeMotion,
+    storage,
+    time_scale::WorldTime,
+};
+use bevy::prelude::*;
+use serde::Serialize;
+
+// one JSON record per line, appended after every run
+pub const TELEMETRY_FILE: &str = "telemetry.jsonl";
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Death {
+    pub cause: Obstacle,
+    // how far into the course, in pixels
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunRecord {
+    pub seed: u64,
+    pub mode: GameMode,
+    pub score: usize,
+    // seconds of play, without the ready screen or the fall after a crash
+    pub duration: f32,
+    pub flaps: usize,
+    // room between the bird's hitbox and the nearer pipe as it passed each
+    // column, negative when it scraped through invulnerable
+    pub clearances: Vec<f32>,
+    pub death: Option<Death>,
+    #[serde(skip)]
+    distance: f32,
+}
+
+// The run being recorded, from the first flap to the game over screen.
+// Tracked after `Update` so the crash that ended a run is in its record.
+#[derive(Default)]
+pub struct RunTelemetry(pub Option<RunRecord>);
+
+pub fn start_run(mode: Res<GameMode>, seed: Res<CourseSeed>, mut telemetry: ResMut<RunTelemetry>) {
+    telemetry.0 = Some(RunRecord {
+        seed: seed.0,
+        mode: *mode,
+        score: 0,
+        duration: 0.0,
+        flaps: 0,
+        clearances: Vec::new(),
+        death: None,
+        distance: 0.0,
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn track_run(
+    time: Res<Time>,
+    world_time: Res<WorldTime>,
+    game_state: Res<State<GameState>>,
+    scoreboard: Res<Scoreboard>,
+    mut telemetry: ResMut<RunTelemetry>,
+    mut flap_events: EventReader<FlapEvent>,
+    mut scored_events: EventReader<PointScoredEvent>,
+    mut collision_events: EventReader<CollisionEvent>,
+    player_query: Query<&Transform, With<Player>>,
+    column_query: Query<(&Transform, &PipeMotion)>,
+) {
+    let record = match &mut telemetry.0 {
+        Some(record) => record,
+        None => return,
+    };
+    let bird = match player_query.get_single() {
+        Ok(transform) => transform.translation,
+        Err(_) => return,
+    };
+
+    if *game_state.current() == GameState::Running {
+        record.duration += time.delta_seconds();
+        record.distance += AUTO_MOVE_SPEED * world_time.delta_seconds();
+    }
+    record.flaps += flap_events.iter().count();
+    for event in scored_events.iter() {
+        if let Ok((transform, motion)) = column_query.get(event.column) {
+            let (offset, gap_size) = motion.gap_at(transform.translation.x);
+            let center = transform.translation.y + offset;
+            let clearance = gap_size / 2.0 - (bird.y - center).abs() - PLAYER.y / 2.0;
+            record.clearances.push(clearance);
+        }
+    }
+    // the crash that ended the run, crashes that do not are not deaths
+    if let Some(event) = collision_events.iter().next() {
+        if record.death.is_none() && record.mode.ends_on_collision() {
+            record.death = Some(Death {
+                cause: event.obstacle,
+                x: record.distance,
+                y: bird.y,
+            });
+        }
+    }
+
+    if matches!(
+        game_state.current(),
+        GameState::GameOver | GameState::LevelComplete
+    ) {
+        record.score = scoreboard.score;
+        write_record(record);
+        telemetry.0 = None;
+    }
+}
+
+fn write_record(record: &RunRecord) {
+    match serde_json::to_string(record) {
+        Ok(line) => storage::append_line(TELEMETRY_FILE, &line),
+        Err(err) => warn!("could not serialize run: {}", err),
+    }
+}
+
+pub struct TelemetryPlugin;
+
+impl Plugin for TelemetryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunTelemetry>()
+            .add_system_set(SystemSet::on_enter(GameState::Running).with_system(start_run))
+            .add_system_to_stage(CoreStage::PostUpdate, track_run);
+    }
+}
@@ -0,0 +1,166 @@
+use crate::{
+    components::*,
+    modes::{respawn_player, GameMode},
+    pipe_behaviors::PipeMotion,
+    save::SaveData,
+    time_scale::running_set,
+    transitions::Transition,
+};
+use bevy::prelude::*;
+
+pub const TIME_ATTACK_SECS: f32 = 60.0;
+pub const CRASH_PENALTY_SECS: f32 = 5.0;
+pub const PENALTY_FLASH_SECS: f32 = 0.6;
+pub const TIME_ATTACK_TABLE_SIZE: usize = 5;
+
+const COUNTDOWN_COLOR: Color = Color::WHITE;
+const PENALTY_COLOR: Color = Color::rgb(0.9, 0.15, 0.1);
+
+pub struct TimeAttackClock {
+    pub remaining: f32,
+    pub penalty_flash: f32,
+}
+
+impl Default for TimeAttackClock {
+    fn default() -> Self {
+        TimeAttackClock {
+            remaining: TIME_ATTACK_SECS,
+            penalty_flash: 0.0,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct CountdownText;
+
+pub fn setup_countdown_text(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let style = TextStyle {
+        font: asset_server.load("flappy-font.ttf"),
+        font_size: 48.0,
+        color: COUNTDOWN_COLOR,
+    };
+    let alignment = TextAlignment {
+        vertical: VerticalAlign::Center,
+        horizontal: HorizontalAlign::Center,
+    };
+
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::with_section(format!("{}", TIME_ATTACK_SECS), style, alignment),
+            transform: Transform::from_xyz(SCREEN.x / 4.0, SCREEN.y / 4.0, 11.0),
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(CountdownText);
+}
+
+pub fn reset_time_attack(
+    mode: Res<GameMode>,
+    mut clock: ResMut<TimeAttackClock>,
+    mut query: Query<&mut Visibility, With<CountdownText>>,
+) {
+    *clock = TimeAttackClock::default();
+    for mut visibility in query.iter_mut() {
+        visibility.is_visible = *mode == GameMode::TimeAttack;
+    }
+}
+
+pub fn time_attack_clock_system(
+    mode: Res<GameMode>,
+    time: Res<Time>,
+    mut clock: ResMut<TimeAttackClock>,
+    mut transition: ResMut<Transition>,
+) {
+    if *mode != GameMode::TimeAttack {
+        return;
+    }
+
+    clock.remaining = (clock.remaining - time.delta_seconds()).max(0.0);
+    clock.penalty_flash = (clock.penalty_flash - time.delta_seconds()).max(0.0);
+    // the fade switches to game over once the screen is black, the run keeps
+    // going until then
+    if clock.remaining <= 0.0 && !transition.active() {
+        transition.fade_to(GameState::GameOver);
+    }
+}
+
+// Crashes cost time and put the bird back into the course instead of
+// ending the run.
+pub fn time_attack_crash_system(
+    mut commands: Commands,
+    mode: Res<GameMode>,
+    mut clock: ResMut<TimeAttackClock>,
+    mut collision_events: EventReader<CollisionEvent>,
+    mut player_query: Query<(Entity, &mut Player, &mut Transform)>,
+    column_query: Query<(&Transform, &PipeMotion), Without<Player>>,
+) {
+    if *mode != GameMode::TimeAttack || collision_events.iter().count() == 0 {
+        return;
+    }
+
+    clock.remaining = (clock.remaining - CRASH_PENALTY_SECS).max(0.0);
+    clock.penalty_flash = PENALTY_FLASH_SECS;
+
+    let (entity, mut player, mut transform) = player_query.single_mut();
+    respawn_player(
+        &mut commands,
+        entity,
+        &mut player,
+        &mut transform,
+        column_query
+            .iter()
+            .map(|(column, motion)| column.translation + Vec3::Y * motion.gap_offset),
+    );
+}
+
+pub fn update_countdown_text(
+    clock: Res<TimeAttackClock>,
+    mut query: Query<&mut Text, With<CountdownText>>,
+) {
+    if !clock.is_changed() {
+        return;
+    }
+
+    for mut text in query.iter_mut() {
+        let section = &mut text.sections[0];
+        section.value = format!("{}", clock.remaining.ceil());
+        section.style.color = if clock.penalty_flash > 0.0 {
+            PENALTY_COLOR
+        } else {
+            COUNTDOWN_COLOR
+        };
+    }
+}
+
+pub fn record_time_attack_score(
+    mode: Res<GameMode>,
+    scoreboard: Res<Scoreboard>,
+    mut save_data: ResMut<SaveData>,
+) {
+    if *mode == GameMode::TimeAttack && save_data.record_time_attack_score(scoreboard.score) {
+        save_data.save();
+    }
+}
+
+pub struct TimeAttackPlugin;
+
+impl Plugin for TimeAttackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TimeAttackClock>()
+            .add_startup_system(setup_countdown_text)
+            .add_system_set(
+                SystemSet::on_enter(GameState::Paused)
+                    .with_system(reset_time_attack)
+                    .with_system(update_countdown_text.after(reset_time_attack)),
+            )
+            .add_system_set(
+                running_set()
+                    .with_system(time_attack_clock_system)
+                    .with_system(time_attack_crash_system)
+                    .with_system(update_countdown_text),
+            )
+            .add_system_set(
+                SystemSet::on_enter(GameState::GameOver).with_system(record_time_attack_score),
+            );
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{modes::GameMode, settings::Settings};
+use bevy::{prelude::*, utils::Duration};
+
+pub const MIN_GAME_SPEED: f32 = 0.25;
+pub const MAX_GAME_SPEED: f32 = 1.0;
+pub const GAME_SPEED_STEP: f32 = 0.25;
+
+// Multipliers applied to the world clock. Everything that moves or
+// animates the course reads `WorldTime` instead of `Time` so a single
+// knob slows the whole world down.
+pub struct GameSpeed {
+    pub practice: f32,
+    pub power_up: f32,
+    // set by the dev time controls, 1 otherwise
+    pub dev: f32,
+}
+
+impl Default for GameSpeed {
+    fn default() -> Self {
+        GameSpeed {
+            practice: MAX_GAME_SPEED,
+            power_up: 1.0,
+            dev: 1.0,
+        }
+    }
+}
+
+impl GameSpeed {
+    pub fn multiplier(&self) -> f32 {
+        self.practice * self.power_up * self.dev
+    }
+}
+
+#[derive(Default)]
+pub struct WorldTime {
+    delta: Duration,
+}
+
+impl WorldTime {
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    pub fn delta_seconds(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+}
+
+// The `Running` systems, which the dev time controls can freeze and step.
+#[cfg(feature = "dev")]
+pub fn running_set() -> SystemSet {
+    SystemSet::new().with_run_criteria(crate::dev_time::running_criteria())
+}
+
+#[cfg(not(feature = "dev"))]
+pub fn running_set() -> SystemSet {
+    SystemSet::on_update(crate::components::GameState::Running)
+}
+
+pub fn clamp_game_speed(speed: f32) -> f32 {
+    ((speed / GAME_SPEED_STEP).round() * GAME_SPEED_STEP).clamp(MIN_GAME_SPEED, MAX_GAME_SPEED)
+}
+
+pub fn update_game_speed(
+    mode: Res<GameMode>,
+    settings: Res<Settings>,
+    mut game_speed: ResMut<GameSpeed>,
+) {
+    let practice = if *mode == GameMode::Practice {
+        clamp_game_speed(settings.practice.game_speed)
+    } else {
+        MAX_GAME_SPEED
+    };
+    if game_speed.practice != practice {
+        game_speed.practice = practice;
+    }
+}
+
+pub fn update_world_time(
+    time: Res<Time>,
+    game_speed: Res<GameSpeed>,
+    mut world_time: ResMut<WorldTime>,
+) {
+    world_time.delta = time.delta().mul_f32(game_speed.multiplier());
+}
+
+pub struct TimeScalePlugin;
+
+impl Plugin for TimeScalePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameSpeed>()
+            .init_resource::<WorldTime>()
+            .add_system_to_stage(CoreStage::PreUpdate, update_game_speed)
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                update_world_time.after(update_game_speed),
+            );
+        #[cfg(feature = "dev")]
+        app.add_plugin(crate::dev_time::DevTimePlugin);
+    }
+}
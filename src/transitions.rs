@@ -0,0 +1,127 @@
+use crate::components::*;
+use bevy::{ecs::schedule::StateError, prelude::*};
+
+// each half of a fade, to black and back
+pub const FADE_SECS: f32 = 0.2;
+
+#[derive(Component)]
+pub struct TransitionOverlay;
+
+// Sent whenever the game state changes, faded or not. Hook for the swoosh.
+pub struct TransitionEvent {
+    pub from: GameState,
+    pub to: GameState,
+}
+
+// A fade to black, the state switches once the screen is black and fades
+// back in after. Input is ignored while one is running.
+pub struct Transition {
+    pub to: Option<GameState>,
+    pub fading_in: bool,
+    pub timer: Timer,
+}
+
+impl Default for Transition {
+    fn default() -> Self {
+        Transition {
+            to: None,
+            fading_in: false,
+            timer: Timer::from_seconds(FADE_SECS, false),
+        }
+    }
+}
+
+impl Transition {
+    pub fn active(&self) -> bool {
+        self.to.is_some() || self.fading_in
+    }
+
+    // Does nothing while another fade is running.
+    pub fn fade_to(&mut self, to: GameState) {
+        if self.active() {
+            return;
+        }
+        self.to = Some(to);
+        self.timer.reset();
+    }
+
+    fn alpha(&self) -> f32 {
+        if self.fading_in {
+            self.timer.percent_left()
+        } else if self.to.is_some() {
+            self.timer.percent()
+        } else {
+            0.0
+        }
+    }
+}
+
+pub fn setup_transition_overlay(mut commands: Commands) {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                ..default()
+            },
+            color: UiColor(Color::NONE),
+            ..default()
+        })
+        .insert(TransitionOverlay);
+}
+
+pub fn run_transition(
+    time: Res<Time>,
+    mut transition: ResMut<Transition>,
+    mut game_state: ResMut<State<GameState>>,
+    mut query: Query<&mut UiColor, With<TransitionOverlay>>,
+) {
+    if !transition.active() {
+        return;
+    }
+
+    transition.timer.tick(time.delta());
+    if transition.timer.finished() {
+        if let Some(to) = transition.to {
+            // another state switch went in this frame, stay black and retry
+            if !matches!(game_state.set(to), Err(StateError::StateAlreadyQueued)) {
+                transition.to = None;
+                transition.fading_in = true;
+                transition.timer.reset();
+            }
+        } else {
+            transition.fading_in = false;
+        }
+    }
+
+    let alpha = transition.alpha();
+    for mut ui_color in query.iter_mut() {
+        ui_color.0 = Color::rgba(0.0, 0.0, 0.0, alpha);
+    }
+}
+
+pub fn send_transition_events(
+    game_state: Res<State<GameState>>,
+    mut previous: Local<Option<GameState>>,
+    mut transition_events: EventWriter<TransitionEvent>,
+) {
+    let current = *game_state.current();
+    if let Some(from) = *previous {
+        if from != current {
+            transition_events.send(TransitionEvent { from, to: current });
+        }
+    }
+    *previous = Some(current);
+}
+
+pub struct TransitionsPlugin;
+
+impl Plugin for TransitionsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Transition>()
+            .add_event::<TransitionEvent>()
+            .add_startup_system(setup_transition_overlay)
+            .add_system(run_transition)
+            .add_system(send_transition_events);
+    }
+}